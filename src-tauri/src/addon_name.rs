@@ -0,0 +1,142 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::ops::Deref;
+
+// ===========================
+// ADDON FOLDER NAMES
+// ===========================
+
+/// Device names Windows refuses as file or folder names
+const RESERVED_NAMES: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+const MAX_NAME_LENGTH: usize = 100;
+
+/// Name of a single folder directly inside Interface/AddOns.
+/// Can only be built from a validated string, so joining it onto the
+/// AddOns path never escapes that folder.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct AddonFolderName(String);
+
+impl AddonFolderName {
+    pub fn new(name: &str) -> Result<Self, String> {
+        if name.is_empty() {
+            return Err("Addon folder name is required".to_string());
+        }
+        if name.len() > MAX_NAME_LENGTH {
+            return Err(format!("Addon folder name is longer than {} characters", MAX_NAME_LENGTH));
+        }
+        if name.trim() != name {
+            return Err(format!("Addon folder name '{}' has leading or trailing spaces", name));
+        }
+        if name == "." || name == ".." || name.contains("..") {
+            return Err(format!("Addon folder name '{}' is not allowed", name));
+        }
+        if name.starts_with('.') || name.ends_with('.') {
+            return Err(format!("Addon folder name '{}' cannot start or end with a dot", name));
+        }
+        if let Some(c) = name
+            .chars()
+            .find(|c| matches!(c, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|') || c.is_control())
+        {
+            return Err(format!("Addon folder name '{}' contains invalid character {:?}", name, c));
+        }
+
+        let stem = name.split('.').next().unwrap_or(name);
+        if RESERVED_NAMES.iter().any(|reserved| stem.eq_ignore_ascii_case(reserved)) {
+            return Err(format!("Addon folder name '{}' is a reserved name", name));
+        }
+
+        Ok(AddonFolderName(name.to_string()))
+    }
+
+    /// For names written in the source code, which are known to be valid
+    pub fn from_static(name: &'static str) -> Self {
+        AddonFolderName::new(name).expect("built-in addon folder name must be valid")
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl TryFrom<String> for AddonFolderName {
+    type Error = String;
+
+    fn try_from(name: String) -> Result<Self, Self::Error> {
+        AddonFolderName::new(&name)
+    }
+}
+
+impl From<AddonFolderName> for String {
+    fn from(name: AddonFolderName) -> Self {
+        name.0
+    }
+}
+
+impl Deref for AddonFolderName {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<std::path::Path> for AddonFolderName {
+    fn as_ref(&self) -> &std::path::Path {
+        std::path::Path::new(&self.0)
+    }
+}
+
+impl fmt::Display for AddonFolderName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_regular_addon_names() {
+        for name in ["Nihui_uf", "WaypointUI", "Details", "DBM-Core", "Plater.Plus", "Addon 2"] {
+            assert!(AddonFolderName::new(name).is_ok(), "{} should be accepted", name);
+        }
+    }
+
+    #[test]
+    fn rejects_path_traversal() {
+        for name in ["..", ".", "..\\..\\WTF", "../../WTF", "Nihui_uf/..", "a..b"] {
+            assert!(AddonFolderName::new(name).is_err(), "{} should be rejected", name);
+        }
+    }
+
+    #[test]
+    fn rejects_separators_and_absolute_paths() {
+        for name in ["Nihui_uf/sub", "Nihui_uf\\sub", "/etc", "\\Windows", "C:\\Windows", "C:", "\\\\server\\share"] {
+            assert!(AddonFolderName::new(name).is_err(), "{} should be rejected", name);
+        }
+    }
+
+    #[test]
+    fn rejects_reserved_and_malformed_names() {
+        for name in ["", " ", "CON", "nul", "COM1", "lpt9.txt", " Nihui_uf", "Nihui_uf ", ".hidden", "trailing.", "a\0b", "a*b"] {
+            assert!(AddonFolderName::new(name).is_err(), "{:?} should be rejected", name);
+        }
+        assert!(AddonFolderName::new(&"a".repeat(MAX_NAME_LENGTH + 1)).is_err());
+    }
+
+    #[test]
+    fn deserialization_validates() {
+        assert!(serde_json::from_str::<AddonFolderName>("\"Nihui_uf\"").is_ok());
+        assert!(serde_json::from_str::<AddonFolderName>("\"..\\\\WTF\"").is_err());
+        assert_eq!(
+            serde_json::to_string(&AddonFolderName::new("Nihui_uf").unwrap()).unwrap(),
+            "\"Nihui_uf\""
+        );
+    }
+}
@@ -0,0 +1,192 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::flavor::GameFlavor;
+
+// ===========================
+// INSTALLED GAME BUILDS
+// ===========================
+
+/// One game client listed in `.build.info`
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct BuildInfo {
+    pub flavor: GameFlavor,
+    pub product: String, // Battle.net product code, e.g. "wow" or "wow_classic_era"
+    pub version: String, // e.g. "11.0.2.56421"
+    pub build: u32,
+    pub interface: u32, // TOC Interface number of the client, e.g. 110002
+}
+
+/// Flavor installed by a Battle.net product code
+fn flavor_for_product(product: &str) -> Option<GameFlavor> {
+    match product {
+        "wow" => Some(GameFlavor::Retail),
+        "wow_classic" => Some(GameFlavor::Classic),
+        "wow_classic_era" => Some(GameFlavor::ClassicEra),
+        "wowt" => Some(GameFlavor::Ptr),
+        "wowxptr" => Some(GameFlavor::Xptr),
+        "wow_beta" => Some(GameFlavor::Beta),
+        "wow_classic_ptr" => Some(GameFlavor::ClassicPtr),
+        _ => None,
+    }
+}
+
+/// Split "11.0.2.56421" into the Interface number (110002) and the build (56421)
+fn parse_version(version: &str) -> Option<(u32, u32)> {
+    let parts: Vec<u32> = version
+        .trim()
+        .split('.')
+        .map(|part| part.parse().ok())
+        .collect::<Option<Vec<u32>>>()?;
+
+    match parts.as_slice() {
+        [major, minor, patch, build] if *minor < 100 && *patch < 100 => {
+            Some((major * 10_000 + minor * 100 + patch, *build))
+        }
+        _ => None,
+    }
+}
+
+/// Parse `.build.info`: a '|' separated table whose header cells are
+/// `Name!TYPE:size`. Only rows for known WoW products are kept, one per
+/// flavor, preferring rows marked active.
+pub fn parse_build_info(content: &str) -> Vec<BuildInfo> {
+    let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+    let mut lines = content.lines().filter(|line| !line.trim().is_empty());
+
+    let Some(header) = lines.next() else {
+        return Vec::new();
+    };
+    let columns: Vec<String> = header
+        .split('|')
+        .map(|cell| cell.split('!').next().unwrap_or(cell).trim().to_ascii_lowercase())
+        .collect();
+    let column = |name: &str| columns.iter().position(|c| c == name);
+    let (Some(product_col), Some(version_col)) = (column("product"), column("version")) else {
+        return Vec::new();
+    };
+    let active_col = column("active");
+
+    let mut builds: Vec<(BuildInfo, bool)> = Vec::new();
+    for line in lines {
+        let cells: Vec<&str> = line.split('|').collect();
+        let product = cells.get(product_col).map(|c| c.trim()).unwrap_or_default();
+        let version = cells.get(version_col).map(|c| c.trim()).unwrap_or_default();
+        let active = active_col
+            .and_then(|col| cells.get(col))
+            .is_some_and(|c| c.trim() == "1");
+
+        let (Some(flavor), Some((interface, build))) = (flavor_for_product(product), parse_version(version)) else {
+            continue;
+        };
+        let info = BuildInfo {
+            flavor,
+            product: product.to_string(),
+            version: version.to_string(),
+            build,
+            interface,
+        };
+
+        match builds.iter_mut().find(|(existing, _)| existing.flavor == flavor) {
+            Some(existing) if active && !existing.1 => *existing = (info, active),
+            Some(_) => {}
+            None => builds.push((info, active)),
+        }
+    }
+
+    let mut builds: Vec<BuildInfo> = builds.into_iter().map(|(info, _)| info).collect();
+    builds.sort_by_key(|info| info.flavor);
+    builds
+}
+
+/// Builds of every client installed in a WoW folder
+pub fn read_build_info(wow_path: &str) -> Result<Vec<BuildInfo>, String> {
+    let path = PathBuf::from(wow_path).join(".build.info");
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read .build.info: {}", e))?;
+    Ok(parse_build_info(&content))
+}
+
+/// Interface number of the installed client of a flavor, if known
+pub fn installed_interface(wow_path: &str, flavor: GameFlavor) -> Option<u32> {
+    read_build_info(wow_path)
+        .ok()?
+        .into_iter()
+        .find(|info| info.flavor == flavor)
+        .map(|info| info.interface)
+}
+
+/// Builds that differ from the last seen build of their flavor.
+/// Flavors without a last seen build are not reported.
+pub fn changed_builds(last_seen: &HashMap<GameFlavor, u32>, builds: &[BuildInfo]) -> Vec<BuildInfo> {
+    builds
+        .iter()
+        .filter(|info| last_seen.get(&info.flavor).is_some_and(|build| *build != info.build))
+        .cloned()
+        .collect()
+}
+
+/// Whether the game would list an addon as out of date: every Interface
+/// number of its TOC is older than the installed client
+pub fn is_interface_outdated(toc_interfaces: &[u32], client_interface: u32) -> bool {
+    !toc_interfaces.is_empty() && toc_interfaces.iter().all(|interface| *interface < client_interface)
+}
+
+/// Get the product, version, build and Interface number of each installed flavor
+#[tauri::command]
+pub fn get_build_info(wow_path: String) -> Result<Vec<BuildInfo>, String> {
+    read_build_info(&wow_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "Branch!STRING:0|Active!DEC:1|Build Key!HEX:16|CDN Key!HEX:16|Install Key!HEX:16|IM Size!DEC:4|CDN Path!STRING:0|CDN Hosts!STRING:0|CDN Servers!STRING:0|Tags!STRING:0|Armadillo!STRING:0|Last Activated!STRING:0|Version!STRING:0|KeyRing!HEX:16|Product!STRING:0\n\
+eu|0|aa|bb|||tpr/wow|eu.cdn.blizzard.com||Windows x86_64 EU? enUS speech?:Windows x86_64 EU? enUS text?||2024-08-20T10:00:00Z|11.0.0.55000|cc|wow\n\
+eu|1|aa|bb|||tpr/wow|eu.cdn.blizzard.com||Windows x86_64 EU? enUS speech?:Windows x86_64 EU? enUS text?||2024-08-21T10:00:00Z|11.0.2.56421|cc|wow\n\
+eu|1|aa|bb|||tpr/wow|eu.cdn.blizzard.com||Windows x86_64 EU? enUS speech?:Windows x86_64 EU? enUS text?||2024-08-21T10:00:00Z|1.15.4.56400|cc|wow_classic_era\n\
+eu|1|aa|bb|||tpr/wow|eu.cdn.blizzard.com||||2024-08-21T10:00:00Z|1.0.0.1|cc|agent\n";
+
+    #[test]
+    fn parses_one_build_per_flavor() {
+        let builds = parse_build_info(SAMPLE);
+
+        assert_eq!(builds.len(), 2);
+        assert_eq!(builds[0].flavor, GameFlavor::Retail);
+        assert_eq!(builds[0].version, "11.0.2.56421");
+        assert_eq!(builds[0].build, 56421);
+        assert_eq!(builds[0].interface, 110002);
+        assert_eq!(builds[1].flavor, GameFlavor::ClassicEra);
+        assert_eq!(builds[1].interface, 11504);
+    }
+
+    #[test]
+    fn ignores_malformed_content() {
+        assert!(parse_build_info("").is_empty());
+        assert!(parse_build_info("Branch!STRING:0|Active!DEC:1\neu|1\n").is_empty());
+        assert_eq!(parse_version("11.0.2"), None);
+    }
+
+    #[test]
+    fn reports_changed_builds_only() {
+        let builds = parse_build_info(SAMPLE);
+        let last_seen = HashMap::from([(GameFlavor::Retail, 56000), (GameFlavor::ClassicEra, 56400)]);
+
+        let changed = changed_builds(&last_seen, &builds);
+        assert_eq!(changed.len(), 1);
+        assert_eq!(changed[0].flavor, GameFlavor::Retail);
+
+        assert!(changed_builds(&HashMap::new(), &builds).is_empty());
+    }
+
+    #[test]
+    fn detects_outdated_interfaces() {
+        assert!(is_interface_outdated(&[100207], 110002));
+        assert!(!is_interface_outdated(&[100207, 110002], 110002));
+        assert!(!is_interface_outdated(&[110005], 110002));
+        assert!(!is_interface_outdated(&[], 110002));
+    }
+}
@@ -0,0 +1,332 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+use crate::addon_name::AddonFolderName;
+use crate::http::HttpClient;
+use crate::version;
+use crate::{get_app_data_dir, AddonDefinition, UpdateMode};
+
+// ===========================
+// ADDON CATALOG
+// ===========================
+
+/// Default location of the remote catalog manifest
+pub const DEFAULT_CATALOG_URL: &str =
+    "https://raw.githubusercontent.com/Nihilop/Nihui_AppUpdater/main/catalog.json";
+
+/// Highest manifest schema version this build understands
+const CATALOG_SCHEMA_VERSION: u32 = 1;
+
+/// How long a cached manifest is used before asking the server again
+const CATALOG_CACHE_TTL: Duration = Duration::from_secs(60 * 60);
+
+const CATALOG_FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CatalogManifest {
+    pub schema_version: u32,
+    #[serde(default)]
+    pub min_app_version: Option<String>,
+    pub addons: Vec<AddonDefinition>,
+}
+
+fn get_catalog_cache_path() -> PathBuf {
+    get_app_data_dir().join("catalog.json")
+}
+
+/// Built-in catalog shipped with the app, used when the remote manifest
+/// and the on-disk cache are both unavailable
+pub fn builtin_addon_definitions() -> Vec<AddonDefinition> {
+    vec![
+        AddonDefinition {
+            local_name: AddonFolderName::from_static("Nihui_uf"),
+            nice_name: "Unit Frames".to_string(),
+            github_owner: "Nihilop".to_string(),
+            github_repo: "Nihui_unitframe".to_string(),
+            description: "Unit frames addon".to_string(),
+            update_mode: UpdateMode::Branch,
+            branch: Some("main".to_string()),
+        },
+        AddonDefinition {
+            local_name: AddonFolderName::from_static("Nihui_ab"),
+            nice_name: "Action Bars".to_string(),
+            github_owner: "Nihilop".to_string(),
+            github_repo: "Nihui_actionbars".to_string(),
+            description: "Action bars addon".to_string(),
+            update_mode: UpdateMode::Branch,
+            branch: Some("main".to_string()),
+        },
+        AddonDefinition {
+            local_name: AddonFolderName::from_static("Nihui_iv"),
+            nice_name: "Inventory".to_string(),
+            github_owner: "Nihilop".to_string(),
+            github_repo: "Nihui_inventory".to_string(),
+            description: "Inventory addon".to_string(),
+            update_mode: UpdateMode::Branch,
+            branch: Some("main".to_string()),
+        },
+        AddonDefinition {
+            local_name: AddonFolderName::from_static("Nihui_cb"),
+            nice_name: "Cast Bars".to_string(),
+            github_owner: "Nihilop".to_string(),
+            github_repo: "Nihui_castbars".to_string(),
+            description: "Castbars addon".to_string(),
+            update_mode: UpdateMode::Branch,
+            branch: Some("main".to_string()),
+        },
+        AddonDefinition {
+            local_name: AddonFolderName::from_static("Nihui_np"),
+            nice_name: "Nameplates".to_string(),
+            github_owner: "Nihilop".to_string(),
+            github_repo: "Nihui_nameplate".to_string(),
+            description: "Nameplate addon".to_string(),
+            update_mode: UpdateMode::Branch,
+            branch: Some("main".to_string()),
+        },
+        AddonDefinition {
+            local_name: AddonFolderName::from_static("Nihui_chat"),
+            nice_name: "Nihui Chatbox".to_string(),
+            github_owner: "Nihilop".to_string(),
+            github_repo: "Nihui_chat".to_string(),
+            description: "Chatbox addon".to_string(),
+            update_mode: UpdateMode::Branch,
+            branch: Some("main".to_string()),
+        },
+        AddonDefinition {
+            local_name: AddonFolderName::from_static("WaypointUI"),
+            nice_name: "Waypoint UI".to_string(),
+            github_owner: "Adaptvx".to_string(),
+            github_repo: "Waypoint-UI".to_string(),
+            description: "waypoint addon".to_string(),
+            update_mode: UpdateMode::Branch,
+            branch: Some("main".to_string()),
+        },
+        // Add more addons here as needed
+        // Example with branch mode:
+        // AddonDefinition {
+        //     local_name: AddonFolderName::from_static("Nihui_nameplate"),
+        //     github_owner: "Nihilop".to_string(),
+        //     github_repo: "Nihui_nameplate".to_string(),
+        //     description: "Nameplate addon".to_string(),
+        //     update_mode: UpdateMode::Branch,
+        //     branch: Some("dev".to_string()),
+        // },
+    ]
+}
+
+/// Check that a manifest can be used by this build of the app
+fn validate_manifest(manifest: &CatalogManifest) -> Result<(), String> {
+    if manifest.schema_version == 0 || manifest.schema_version > CATALOG_SCHEMA_VERSION {
+        return Err(format!(
+            "Unsupported catalog schema version {} (supported: {})",
+            manifest.schema_version, CATALOG_SCHEMA_VERSION
+        ));
+    }
+
+    if let Some(min_app_version) = &manifest.min_app_version {
+        if !version::is_at_least(env!("CARGO_PKG_VERSION"), min_app_version) {
+            return Err(format!(
+                "Catalog requires app version {} or newer",
+                min_app_version
+            ));
+        }
+    }
+
+    if manifest.addons.is_empty() {
+        return Err("Catalog does not contain any addon".to_string());
+    }
+
+    // Folder names are checked when parsing; owner and repo end up in API
+    // URLs and download paths, so they follow the custom addon rules too
+    let mut seen: Vec<&AddonFolderName> = Vec::new();
+    for addon in &manifest.addons {
+        if !crate::is_valid_github_owner(&addon.github_owner)
+            || !crate::is_valid_github_repo(&addon.github_repo)
+        {
+            return Err(format!(
+                "Catalog entry '{}' has an invalid repository '{}/{}'",
+                addon.local_name, addon.github_owner, addon.github_repo
+            ));
+        }
+        if seen.iter().any(|name| name.eq_ignore_ascii_case(&addon.local_name)) {
+            return Err(format!("Catalog lists '{}' more than once", addon.local_name));
+        }
+        seen.push(&addon.local_name);
+    }
+
+    Ok(())
+}
+
+/// Read the cached manifest, if present and still usable
+fn read_cached_manifest() -> Option<CatalogManifest> {
+    let content = fs::read_to_string(get_catalog_cache_path()).ok()?;
+    let manifest: CatalogManifest = serde_json::from_str(&content).ok()?;
+    validate_manifest(&manifest).ok()?;
+    Some(manifest)
+}
+
+fn is_cache_fresh() -> bool {
+    fs::metadata(get_catalog_cache_path())
+        .and_then(|meta| meta.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+        .map(|age| age < CATALOG_CACHE_TTL)
+        .unwrap_or(false)
+}
+
+/// Addon definitions from the cached manifest, or the built-in list when offline
+pub fn load_addon_definitions() -> Vec<AddonDefinition> {
+    read_cached_manifest()
+        .map(|manifest| manifest.addons)
+        .unwrap_or_else(builtin_addon_definitions)
+}
+
+/// Download the manifest at `url` and validate it
+pub async fn fetch_catalog(http: &HttpClient, url: &str) -> Result<CatalogManifest, String> {
+    let response = http
+        .get(url)
+        .timeout(CATALOG_FETCH_TIMEOUT)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch catalog: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(format!("Catalog server error: {}", response.status()));
+    }
+
+    let manifest: CatalogManifest = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse catalog JSON: {}", e))?;
+
+    validate_manifest(&manifest)?;
+
+    Ok(manifest)
+}
+
+/// Refresh the on-disk catalog cache from `url`.
+/// Skips the request while the cache is fresh unless `force` is set.
+pub async fn refresh_catalog(http: &HttpClient, url: &str, force: bool) -> Result<(), String> {
+    if !force && is_cache_fresh() && read_cached_manifest().is_some() {
+        return Ok(());
+    }
+
+    let manifest = fetch_catalog(http, url).await?;
+
+    let cache_path = get_catalog_cache_path();
+    if let Some(parent) = cache_path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create cache directory: {}", e))?;
+    }
+
+    let json = serde_json::to_string_pretty(&manifest)
+        .map_err(|e| format!("Failed to serialize catalog: {}", e))?;
+
+    fs::write(&cache_path, json)
+        .map_err(|e| format!("Failed to write catalog cache: {}", e))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest(addons: Vec<AddonDefinition>) -> CatalogManifest {
+        CatalogManifest {
+            schema_version: CATALOG_SCHEMA_VERSION,
+            min_app_version: None,
+            addons,
+        }
+    }
+
+    fn addon(local_name: &'static str, github_owner: &str, github_repo: &str) -> AddonDefinition {
+        AddonDefinition {
+            local_name: AddonFolderName::from_static(local_name),
+            nice_name: local_name.to_string(),
+            github_owner: github_owner.to_string(),
+            github_repo: github_repo.to_string(),
+            description: String::new(),
+            update_mode: UpdateMode::Release,
+            branch: None,
+        }
+    }
+
+    #[test]
+    fn accepts_the_builtin_catalog() {
+        assert!(validate_manifest(&manifest(builtin_addon_definitions())).is_ok());
+    }
+
+    #[test]
+    fn rejects_unsupported_schema_versions() {
+        for schema_version in [0, CATALOG_SCHEMA_VERSION + 1] {
+            let catalog = CatalogManifest {
+                schema_version,
+                ..manifest(builtin_addon_definitions())
+            };
+            assert!(validate_manifest(&catalog).unwrap_err().contains("schema version"));
+        }
+    }
+
+    #[test]
+    fn rejects_catalogs_for_newer_apps() {
+        let catalog = CatalogManifest {
+            min_app_version: Some("999.0.0".to_string()),
+            ..manifest(builtin_addon_definitions())
+        };
+        assert!(validate_manifest(&catalog).unwrap_err().contains("999.0.0"));
+
+        let catalog = CatalogManifest {
+            min_app_version: Some("0.0.1".to_string()),
+            ..manifest(builtin_addon_definitions())
+        };
+        assert!(validate_manifest(&catalog).is_ok());
+    }
+
+    #[test]
+    fn rejects_empty_catalogs() {
+        assert!(validate_manifest(&manifest(Vec::new())).is_err());
+    }
+
+    #[test]
+    fn rejects_duplicate_addons() {
+        let catalog = manifest(vec![
+            addon("Nihui_uf", "Nihilop", "Nihui_unitframe"),
+            addon("nihui_UF", "Nihilop", "Nihui_other"),
+        ]);
+        assert!(validate_manifest(&catalog).unwrap_err().contains("more than once"));
+    }
+
+    #[test]
+    fn rejects_owners_and_repos_that_are_not_github_names() {
+        for (owner, repo) in [
+            ("../x", "Nihui_uf"),
+            ("a/b", "Nihui_uf"),
+            ("Nihilop", "a/b"),
+            ("Nihilop", ".."),
+            ("", "Nihui_uf"),
+        ] {
+            let catalog = manifest(vec![addon("Nihui_uf", owner, repo)]);
+            assert!(validate_manifest(&catalog).is_err(), "{}/{} was accepted", owner, repo);
+        }
+    }
+
+    #[test]
+    fn rejects_folder_names_with_paths_when_parsing() {
+        let json = r#"{
+            "schema_version": 1,
+            "addons": [{
+                "local_name": "../WTF",
+                "nice_name": "Evil",
+                "github_owner": "Nihilop",
+                "github_repo": "Nihui_uf",
+                "description": "",
+                "update_mode": "release",
+                "branch": null
+            }]
+        }"#;
+        assert!(serde_json::from_str::<CatalogManifest>(json).is_err());
+    }
+}
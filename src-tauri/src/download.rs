@@ -0,0 +1,395 @@
+use futures_util::StreamExt;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::Emitter;
+use tokio::sync::Notify;
+use zip::ZipArchive;
+
+use crate::addon_name::AddonFolderName;
+use crate::flavor::GameFlavor;
+use crate::http::HttpClient;
+
+// ===========================
+// STREAMING DOWNLOADS
+// ===========================
+
+/// Event emitted while an addon is being installed
+pub const INSTALL_PROGRESS_EVENT: &str = "install-progress";
+
+/// Minimum time between two progress events of the same phase
+const PROGRESS_THROTTLE: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum InstallPhase {
+    Download,
+    Extract,
+    Copy,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct InstallProgress {
+    pub addon: String,
+    pub flavor: GameFlavor,
+    pub phase: InstallPhase,
+    pub progress: u64,      // Bytes while downloading, files while extracting/copying
+    pub total: Option<u64>, // None when the server did not send a length
+}
+
+/// Emits throttled progress events for one install
+pub struct ProgressReporter {
+    app: Option<tauri::AppHandle>, // None = progress is not reported
+    addon: String,
+    flavor: GameFlavor,
+    last_emit: Option<(InstallPhase, Instant)>,
+}
+
+impl ProgressReporter {
+    pub fn new(app: tauri::AppHandle, flavor: GameFlavor, addon: &str) -> Self {
+        ProgressReporter {
+            app: Some(app),
+            addon: addon.to_string(),
+            flavor,
+            last_emit: None,
+        }
+    }
+
+    #[cfg(test)]
+    pub fn silent(flavor: GameFlavor, addon: &str) -> Self {
+        ProgressReporter {
+            app: None,
+            addon: addon.to_string(),
+            flavor,
+            last_emit: None,
+        }
+    }
+
+    pub fn report(&mut self, phase: InstallPhase, progress: u64, total: Option<u64>) {
+        let done = total.is_some_and(|total| progress >= total);
+        if let Some((last_phase, at)) = self.last_emit {
+            if last_phase == phase && !done && at.elapsed() < PROGRESS_THROTTLE {
+                return;
+            }
+        }
+        self.last_emit = Some((phase, Instant::now()));
+
+        let Some(app) = &self.app else {
+            return;
+        };
+        let _ = app.emit(
+            INSTALL_PROGRESS_EVENT,
+            InstallProgress {
+                addon: self.addon.clone(),
+                flavor: self.flavor,
+                phase,
+                progress,
+                total,
+            },
+        );
+    }
+}
+
+/// Cancellation flag shared between an install and `cancel_install`
+#[derive(Clone, Default)]
+pub struct CancelFlag {
+    cancelled: Arc<AtomicBool>,
+    notify: Arc<Notify>, // Wakes the tasks waiting in `cancelled`
+}
+
+impl CancelFlag {
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+        self.notify.notify_waiters();
+    }
+
+    /// Resolve once the flag is set
+    pub async fn cancelled(&self) {
+        let notified = self.notify.notified();
+        tokio::pin!(notified);
+        // Registered before the check, so a cancel in between is not missed
+        notified.as_mut().enable();
+        if self.cancelled.load(Ordering::SeqCst) {
+            return;
+        }
+        notified.await;
+    }
+
+    /// Fail with a cancellation error once the flag is set
+    pub fn check(&self, addon: &str) -> Result<(), String> {
+        if self.cancelled.load(Ordering::SeqCst) {
+            Err(format!("Installation of {} was cancelled", addon))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+type ActiveInstalls = Arc<Mutex<HashMap<(GameFlavor, String), CancelFlag>>>;
+
+/// Installs in flight, kept in Tauri managed state
+#[derive(Default)]
+pub struct InstallRegistry {
+    active: ActiveInstalls,
+}
+
+/// Removes an install from the registry when dropped
+pub struct InstallGuard {
+    active: ActiveInstalls,
+    key: (GameFlavor, String),
+    pub cancel: CancelFlag,
+}
+
+impl Drop for InstallGuard {
+    fn drop(&mut self) {
+        if let Ok(mut active) = self.active.lock() {
+            active.remove(&self.key);
+        }
+    }
+}
+
+impl InstallRegistry {
+    /// Register an install, or any other change to an addon folder, refusing
+    /// a second one for the same addon
+    pub fn begin(&self, flavor: GameFlavor, addon: &str) -> Result<InstallGuard, String> {
+        let mut active = self.active.lock().map_err(|_| "Install registry is unavailable".to_string())?;
+        let key = (flavor, addon.to_string());
+        if active.contains_key(&key) {
+            return Err(format!("{} is already being installed or changed", addon));
+        }
+
+        let cancel = CancelFlag::default();
+        active.insert(key.clone(), cancel.clone());
+
+        Ok(InstallGuard {
+            active: self.active.clone(),
+            key,
+            cancel,
+        })
+    }
+
+    /// Request cancellation of an install; false if none is running
+    pub fn cancel(&self, flavor: GameFlavor, addon: &str) -> bool {
+        match self.active.lock() {
+            Ok(active) => match active.get(&(flavor, addon.to_string())) {
+                Some(flag) => {
+                    flag.cancel();
+                    true
+                }
+                None => false,
+            },
+            Err(_) => false,
+        }
+    }
+}
+
+/// Stream a download to `dest` without buffering it in memory
+pub async fn download_to_file(
+    http: &HttpClient,
+    url: &str,
+    dest: &Path,
+    addon: &str,
+    reporter: &mut ProgressReporter,
+    cancel: &CancelFlag,
+) -> Result<(), String> {
+    // Like the chunks below, a server that never answers can be cancelled
+    let response = tokio::select! {
+        response = http.github_get(url).send() => response,
+        _ = cancel.cancelled() => return cancel.check(addon),
+    }
+    .map_err(|e| format!("Failed to download addon: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(format!(
+            "Download failed: {}",
+            http.status_error(response.status(), &format!("the archive of {}", addon))
+        ));
+    }
+
+    let total = response.content_length();
+    let mut file = fs::File::create(dest)
+        .map_err(|e| format!("Failed to create download file: {}", e))?;
+    let mut downloaded: u64 = 0;
+    let mut stream = response.bytes_stream();
+
+    reporter.report(InstallPhase::Download, 0, total);
+
+    loop {
+        // Raced against the flag, so a stalled connection can be cancelled
+        // without waiting for the read timeout
+        let chunk = tokio::select! {
+            chunk = stream.next() => chunk,
+            _ = cancel.cancelled() => return cancel.check(addon),
+        };
+        let Some(chunk) = chunk else {
+            break;
+        };
+        cancel.check(addon)?;
+
+        let chunk = chunk.map_err(|e| format!("Failed to read download data: {}", e))?;
+        file.write_all(&chunk)
+            .map_err(|e| format!("Failed to write download data: {}", e))?;
+
+        downloaded += chunk.len() as u64;
+        reporter.report(InstallPhase::Download, downloaded, total);
+    }
+
+    file.flush()
+        .map_err(|e| format!("Failed to write download data: {}", e))?;
+    reporter.report(InstallPhase::Download, downloaded, Some(downloaded));
+
+    Ok(())
+}
+
+/// Extract a ZIP archive into `dest_dir`, checking for cancellation per entry
+pub fn extract_zip(
+    zip_path: &Path,
+    dest_dir: &Path,
+    addon: &str,
+    reporter: &mut ProgressReporter,
+    cancel: &CancelFlag,
+) -> Result<(), String> {
+    let zip_file = fs::File::open(zip_path)
+        .map_err(|e| format!("Failed to open downloaded archive: {}", e))?;
+    let mut archive = ZipArchive::new(zip_file)
+        .map_err(|e| format!("Failed to open ZIP archive: {}", e))?;
+
+    let total = archive.len() as u64;
+    reporter.report(InstallPhase::Extract, 0, Some(total));
+
+    for i in 0..archive.len() {
+        cancel.check(addon)?;
+
+        let mut file = archive.by_index(i)
+            .map_err(|e| format!("Failed to read ZIP entry: {}", e))?;
+
+        let outpath = match file.enclosed_name() {
+            Some(path) => dest_dir.join(path),
+            None => continue,
+        };
+
+        if file.name().ends_with('/') {
+            fs::create_dir_all(&outpath)
+                .map_err(|e| format!("Failed to create directory: {}", e))?;
+        } else {
+            if let Some(p) = outpath.parent() {
+                if !p.exists() {
+                    fs::create_dir_all(p)
+                        .map_err(|e| format!("Failed to create parent directory: {}", e))?;
+                }
+            }
+            let mut outfile = fs::File::create(&outpath)
+                .map_err(|e| format!("Failed to create file: {}", e))?;
+            std::io::copy(&mut file, &mut outfile)
+                .map_err(|e| format!("Failed to extract file: {}", e))?;
+        }
+
+        reporter.report(InstallPhase::Extract, i as u64 + 1, Some(total));
+    }
+
+    Ok(())
+}
+
+/// Number of files below `dir`, for copy progress
+pub fn count_files(dir: &Path) -> u64 {
+    fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| {
+                    let path = entry.path();
+                    if path.is_dir() { count_files(&path) } else { 1 }
+                })
+                .sum()
+        })
+        .unwrap_or(0)
+}
+
+/// Abort an addon download or extraction in flight
+#[tauri::command]
+pub fn cancel_install(
+    addon_name: AddonFolderName,
+    flavor: GameFlavor,
+    registry: tauri::State<'_, InstallRegistry>,
+) -> Result<(), String> {
+    if registry.cancel(flavor, &addon_name) {
+        Ok(())
+    } else {
+        Err(format!("No installation of {} is running", addon_name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stub_server::{StubResponse, StubServer};
+    use crate::AppConfig;
+
+    #[tokio::test]
+    async fn cancels_a_stalled_download() {
+        // Sends part of the archive, then nothing until long after the test
+        let server = StubServer::start(vec![StubResponse::status(200).stalled_body(b"PK", 1_000_000)]).await;
+        let http = HttpClient::new(&AppConfig::default(), None).unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let mut reporter = ProgressReporter::silent(GameFlavor::Retail, "Nihui_uf");
+        let cancel = CancelFlag::default();
+        let started = Instant::now();
+
+        let url = format!("{}/archive.zip", server.url);
+        let dest = dir.path().join("download.zip");
+        let (result, _) = tokio::join!(
+            download_to_file(&http, &url, &dest, "Nihui_uf", &mut reporter, &cancel),
+            async {
+                tokio::time::sleep(Duration::from_millis(200)).await;
+                cancel.cancel();
+            }
+        );
+
+        assert_eq!(result, Err("Installation of Nihui_uf was cancelled".to_string()));
+        // Well before the read timeout
+        assert!(started.elapsed() < Duration::from_secs(10));
+    }
+
+    #[tokio::test]
+    async fn cancels_before_the_server_answers() {
+        let server = StubServer::start(vec![StubResponse::no_answer()]).await;
+        let http = HttpClient::new(&AppConfig::default(), None).unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let mut reporter = ProgressReporter::silent(GameFlavor::Retail, "Nihui_uf");
+        let cancel = CancelFlag::default();
+        let started = Instant::now();
+
+        let url = format!("{}/archive.zip", server.url);
+        let dest = dir.path().join("download.zip");
+        let (result, _) = tokio::join!(
+            download_to_file(&http, &url, &dest, "Nihui_uf", &mut reporter, &cancel),
+            async {
+                tokio::time::sleep(Duration::from_millis(200)).await;
+                cancel.cancel();
+            }
+        );
+
+        assert_eq!(result, Err("Installation of Nihui_uf was cancelled".to_string()));
+        assert!(started.elapsed() < Duration::from_secs(10));
+        assert!(!dest.exists());
+    }
+
+    #[test]
+    fn registry_refuses_a_second_install_of_the_same_addon() {
+        let registry = InstallRegistry::default();
+        let guard = registry.begin(GameFlavor::Retail, "Nihui_uf").unwrap();
+
+        assert!(registry.begin(GameFlavor::Retail, "Nihui_uf").is_err());
+        assert!(registry.begin(GameFlavor::Classic, "Nihui_uf").is_ok());
+        assert!(registry.cancel(GameFlavor::Retail, "Nihui_uf"));
+        assert!(guard.cancel.check("Nihui_uf").is_err());
+
+        drop(guard);
+        assert!(registry.begin(GameFlavor::Retail, "Nihui_uf").is_ok());
+    }
+}
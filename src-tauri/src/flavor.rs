@@ -0,0 +1,107 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use crate::toc::TocClient;
+
+// ===========================
+// GAME FLAVORS
+// ===========================
+
+/// One game client inside the WoW install folder, named after its directory
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize, Default)]
+pub enum GameFlavor {
+    #[default]
+    #[serde(rename = "_retail_")]
+    Retail,
+    #[serde(rename = "_classic_")]
+    Classic,
+    #[serde(rename = "_classic_era_")]
+    ClassicEra,
+    #[serde(rename = "_ptr_")]
+    Ptr,
+    #[serde(rename = "_xptr_")]
+    Xptr,
+    #[serde(rename = "_beta_")]
+    Beta,
+    #[serde(rename = "_classic_ptr_")]
+    ClassicPtr,
+}
+
+impl GameFlavor {
+    pub const ALL: [GameFlavor; 7] = [
+        GameFlavor::Retail,
+        GameFlavor::Classic,
+        GameFlavor::ClassicEra,
+        GameFlavor::Ptr,
+        GameFlavor::Xptr,
+        GameFlavor::Beta,
+        GameFlavor::ClassicPtr,
+    ];
+
+    /// Directory of the flavor inside the WoW install folder
+    pub fn dir_name(self) -> &'static str {
+        match self {
+            GameFlavor::Retail => "_retail_",
+            GameFlavor::Classic => "_classic_",
+            GameFlavor::ClassicEra => "_classic_era_",
+            GameFlavor::Ptr => "_ptr_",
+            GameFlavor::Xptr => "_xptr_",
+            GameFlavor::Beta => "_beta_",
+            GameFlavor::ClassicPtr => "_classic_ptr_",
+        }
+    }
+
+    /// Human readable name, for notifications
+    pub fn label(self) -> &'static str {
+        match self {
+            GameFlavor::Retail => "Retail",
+            GameFlavor::Classic => "Classic",
+            GameFlavor::ClassicEra => "Classic Era",
+            GameFlavor::Ptr => "PTR",
+            GameFlavor::Xptr => "XPTR",
+            GameFlavor::Beta => "Beta",
+            GameFlavor::ClassicPtr => "Classic PTR",
+        }
+    }
+
+    /// Which TOC files the flavor's client loads
+    pub fn toc_client(self) -> TocClient {
+        match self {
+            GameFlavor::Retail | GameFlavor::Ptr | GameFlavor::Xptr | GameFlavor::Beta => TocClient::Mainline,
+            GameFlavor::Classic | GameFlavor::ClassicPtr => TocClient::Mists,
+            GameFlavor::ClassicEra => TocClient::Vanilla,
+        }
+    }
+
+    pub fn root_path(self, wow_path: &str) -> PathBuf {
+        PathBuf::from(wow_path).join(self.dir_name())
+    }
+
+    pub fn addons_path(self, wow_path: &str) -> PathBuf {
+        self.root_path(wow_path).join("Interface").join("AddOns")
+    }
+
+    pub fn wtf_path(self, wow_path: &str) -> PathBuf {
+        self.root_path(wow_path).join("WTF")
+    }
+
+    /// Subfolder of an app data directory holding this flavor's data
+    pub fn data_dir(self, base: &Path) -> PathBuf {
+        base.join(self.dir_name())
+    }
+}
+
+impl fmt::Display for GameFlavor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.dir_name())
+    }
+}
+
+/// Flavors installed in a WoW folder, i.e. with an Interface/AddOns directory
+pub fn detect_flavors(wow_path: &Path) -> Vec<GameFlavor> {
+    GameFlavor::ALL
+        .into_iter()
+        .filter(|flavor| wow_path.join(flavor.dir_name()).join("Interface").join("AddOns").is_dir())
+        .collect()
+}
@@ -0,0 +1,83 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::get_app_data_dir;
+
+// ===========================
+// GITHUB RESPONSE CACHE
+// ===========================
+
+/// Last successful response to a GitHub URL, replayed when GitHub answers
+/// 304 Not Modified to a conditional request
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct CachedResponse {
+    pub url: String,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    pub body: String,
+}
+
+/// Where responses are cached
+pub fn cache_dir() -> PathBuf {
+    get_app_data_dir().join("github_cache")
+}
+
+/// One file per URL, named after its hash so any URL makes a valid file name
+fn entry_path(cache_dir: &Path, url: &str) -> PathBuf {
+    let hash = Sha256::digest(url.as_bytes());
+    cache_dir.join(format!("{:x}.json", hash))
+}
+
+pub fn load(cache_dir: &Path, url: &str) -> Option<CachedResponse> {
+    fs::read_to_string(entry_path(cache_dir, url))
+        .ok()
+        .and_then(|content| serde_json::from_str::<CachedResponse>(&content).ok())
+        .filter(|entry| entry.url == url)
+}
+
+pub fn store(cache_dir: &Path, entry: &CachedResponse) -> Result<(), String> {
+    fs::create_dir_all(cache_dir)
+        .map_err(|e| format!("Failed to create GitHub cache directory: {}", e))?;
+
+    let json = serde_json::to_string(entry)
+        .map_err(|e| format!("Failed to serialize cached response: {}", e))?;
+
+    fs::write(entry_path(cache_dir, &entry.url), json)
+        .map_err(|e| format!("Failed to write cached response: {}", e))
+}
+
+/// Drop every cached response
+pub fn clear(cache_dir: &Path) -> Result<(), String> {
+    match fs::remove_dir_all(cache_dir) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(format!("Failed to clear GitHub cache: {}", e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stores_and_loads_by_url() {
+        let dir = tempfile::tempdir().unwrap();
+        let entry = CachedResponse {
+            url: "https://api.github.com/repos/o/r/releases/latest".to_string(),
+            etag: Some("W/\"abc\"".to_string()),
+            last_modified: None,
+            body: "{}".to_string(),
+        };
+
+        store(dir.path(), &entry).unwrap();
+
+        assert_eq!(load(dir.path(), &entry.url), Some(entry.clone()));
+        assert_eq!(load(dir.path(), "https://api.github.com/repos/o/r/branches"), None);
+
+        clear(dir.path()).unwrap();
+        assert_eq!(load(dir.path(), &entry.url), None);
+        clear(dir.path()).unwrap();
+    }
+}
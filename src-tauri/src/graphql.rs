@@ -0,0 +1,226 @@
+use serde_json::Value;
+
+use crate::flavor::GameFlavor;
+use crate::http::HttpClient;
+use crate::toc;
+use crate::update_check::RemoteState;
+use crate::{AddonDefinition, UpdateMode};
+
+// ===========================
+// GITHUB GRAPHQL CHECKER
+// ===========================
+
+/// GraphQL string literal; the escaping rules are the same as JSON's
+fn literal(value: &str) -> String {
+    serde_json::to_string(value).unwrap_or_default()
+}
+
+fn alias(index: usize) -> String {
+    format!("a{}", index)
+}
+
+/// One query for the remote state of every addon: an aliased `repository`
+/// per addon (a0, a1...) and, in branch mode, an aliased `file` per TOC
+/// candidate (t0, t1...) read at the head commit of the branch
+pub fn build_query(flavor: GameFlavor, addons: &[AddonDefinition]) -> String {
+    let mut query = String::from("query {\n");
+
+    for (index, addon_def) in addons.iter().enumerate() {
+        let fields = match (&addon_def.update_mode, &addon_def.branch) {
+            (UpdateMode::Release, _) => "    latestRelease { tagName }\n".to_string(),
+            (UpdateMode::Branch, Some(branch)) => {
+                let files: String = toc::toc_candidates(&addon_def.local_name, flavor.toc_client())
+                    .iter()
+                    .enumerate()
+                    .map(|(toc_index, toc_name)| {
+                        format!(
+                            "          t{}: file(path: {}) {{ object {{ ... on Blob {{ text }} }} }}\n",
+                            toc_index,
+                            literal(toc_name)
+                        )
+                    })
+                    .collect();
+                format!(
+                    "    ref(qualifiedName: {}) {{\n      target {{\n        oid\n        ... on Commit {{\n{}        }}\n      }}\n    }}\n",
+                    literal(&format!("refs/heads/{}", branch)),
+                    files
+                )
+            }
+            // Reported as an error when parsing, nothing to ask for
+            (UpdateMode::Branch, None) => continue,
+        };
+
+        query.push_str(&format!(
+            "  {}: repository(owner: {}, name: {}) {{\n{}  }}\n",
+            alias(index),
+            literal(&addon_def.github_owner),
+            literal(&addon_def.github_repo),
+            fields
+        ));
+    }
+
+    query.push('}');
+    query
+}
+
+/// Message of the first GraphQL error about an alias
+fn error_for(response: &Value, alias: &str) -> Option<String> {
+    response["errors"]
+        .as_array()?
+        .iter()
+        .find(|error| error["path"][0].as_str() == Some(alias))
+        .and_then(|error| error["message"].as_str())
+        .map(str::to_string)
+}
+
+fn parse_repository(
+    response: &Value,
+    index: usize,
+    flavor: GameFlavor,
+    addon_def: &AddonDefinition,
+) -> Result<RemoteState, String> {
+    if addon_def.update_mode == UpdateMode::Branch && addon_def.branch.is_none() {
+        return Err("Branch name is required for branch mode".to_string());
+    }
+
+    let alias = alias(index);
+    let repository = &response["data"][&alias];
+    if repository.is_null() {
+        return Err(error_for(response, &alias).unwrap_or_else(|| {
+            format!("Repository {}/{} not found", addon_def.github_owner, addon_def.github_repo)
+        }));
+    }
+
+    match addon_def.update_mode {
+        UpdateMode::Release => Ok(RemoteState {
+            release_tag: repository["latestRelease"]["tagName"].as_str().map(str::to_string),
+            ..RemoteState::default()
+        }),
+        UpdateMode::Branch => {
+            let branch = addon_def.branch.as_deref().unwrap_or_default();
+            let target = &repository["ref"]["target"];
+            let head_commit = target["oid"]
+                .as_str()
+                .ok_or_else(|| format!("Branch {} not found", branch))?;
+
+            // First TOC candidate present at the head, in the order the game loads them
+            let candidates = toc::toc_candidates(&addon_def.local_name, flavor.toc_client()).len();
+            let toc_version = (0..candidates)
+                .find_map(|toc_index| target[format!("t{}", toc_index)]["object"]["text"].as_str())
+                .and_then(|content| toc::TocFile::parse(content).version);
+
+            Ok(RemoteState {
+                head_commit: Some(head_commit.to_string()),
+                toc_version,
+                ..RemoteState::default()
+            })
+        }
+    }
+}
+
+/// Remote state of each addon, in the order of `addons`, from a GraphQL
+/// response. Fails as a whole when the response has no data at all.
+pub fn parse_response(
+    response: &Value,
+    flavor: GameFlavor,
+    addons: &[AddonDefinition],
+) -> Result<Vec<Result<RemoteState, String>>, String> {
+    if !response["data"].is_object() {
+        let message = response["errors"][0]["message"].as_str().unwrap_or("No data in GraphQL response");
+        return Err(message.to_string());
+    }
+
+    Ok(addons
+        .iter()
+        .enumerate()
+        .map(|(index, addon_def)| parse_repository(response, index, flavor, addon_def))
+        .collect())
+}
+
+/// Fetch the remote state of every addon with a single GraphQL query
+pub async fn fetch_remote_states(
+    http: &HttpClient,
+    flavor: GameFlavor,
+    addons: &[AddonDefinition],
+) -> Result<Vec<Result<RemoteState, String>>, String> {
+    if addons.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let response = http.github_graphql(&build_query(flavor, addons)).await?;
+    parse_response(&response, flavor, addons)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::addon_name::AddonFolderName;
+    use serde_json::json;
+
+    fn addon(name: &'static str, update_mode: UpdateMode, branch: Option<&str>) -> AddonDefinition {
+        AddonDefinition {
+            local_name: AddonFolderName::from_static(name),
+            nice_name: name.to_string(),
+            github_owner: "Nihilop".to_string(),
+            github_repo: name.to_string(),
+            description: String::new(),
+            update_mode,
+            branch: branch.map(str::to_string),
+        }
+    }
+
+    fn addons() -> Vec<AddonDefinition> {
+        vec![
+            addon("Nihui_uf", UpdateMode::Release, None),
+            addon("Nihui_cb", UpdateMode::Branch, Some("main")),
+            addon("Nihui_gone", UpdateMode::Branch, Some("main")),
+            addon("Nihui_nobranch", UpdateMode::Branch, None),
+        ]
+    }
+
+    #[test]
+    fn builds_one_aliased_field_per_addon() {
+        let query = build_query(GameFlavor::Retail, &addons());
+
+        assert!(query.contains("a0: repository(owner: \"Nihilop\", name: \"Nihui_uf\")"));
+        assert!(query.contains("latestRelease { tagName }"));
+        assert!(query.contains("a1: repository(owner: \"Nihilop\", name: \"Nihui_cb\")"));
+        assert!(query.contains("ref(qualifiedName: \"refs/heads/main\")"));
+        assert!(query.contains("t0: file(path: \"Nihui_cb_Mainline.toc\")"));
+        assert!(!query.contains("a3:"));
+    }
+
+    #[test]
+    fn parses_states_and_per_addon_errors() {
+        let response = json!({
+            "data": {
+                "a0": { "latestRelease": { "tagName": "v1.2.0" } },
+                "a1": { "ref": { "target": {
+                    "oid": "abc123",
+                    "t0": null,
+                    "t1": null,
+                    "t2": { "object": { "text": "## Interface: 110002\n## Version: 2.0.1\n" } }
+                } } },
+                "a2": null
+            },
+            "errors": [
+                { "path": ["a2"], "message": "Could not resolve to a Repository with the name 'Nihilop/Nihui_gone'." }
+            ]
+        });
+
+        let states = parse_response(&response, GameFlavor::Retail, &addons()).unwrap();
+
+        assert_eq!(states[0].as_ref().unwrap().release_tag.as_deref(), Some("v1.2.0"));
+        let branch = states[1].as_ref().unwrap();
+        assert_eq!(branch.head_commit.as_deref(), Some("abc123"));
+        assert_eq!(branch.toc_version.as_deref(), Some("2.0.1"));
+        assert!(states[2].as_ref().unwrap_err().contains("Could not resolve"));
+        assert_eq!(states[3].as_ref().unwrap_err(), "Branch name is required for branch mode");
+    }
+
+    #[test]
+    fn fails_without_data() {
+        let response = json!({ "errors": [{ "message": "Bad credentials" }] });
+        assert_eq!(parse_response(&response, GameFlavor::Retail, &addons()), Err("Bad credentials".to_string()));
+    }
+}
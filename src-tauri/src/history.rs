@@ -0,0 +1,316 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::Manager;
+
+use crate::addon_name::AddonFolderName;
+use crate::flavor::GameFlavor;
+use crate::install_record::{self, InstallRecord};
+use crate::{copy_dir_all, download, get_app_data_dir, load_config, read_addon_version, swap};
+
+// ===========================
+// ADDON VERSION HISTORY
+// ===========================
+
+/// Default number of previous versions kept per addon
+pub const DEFAULT_VERSION_RETENTION: usize = 3;
+
+/// A previously installed version kept in the local cache
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AddonVersion {
+    pub id: String,
+    pub local_name: AddonFolderName,
+    pub version: Option<String>, // TOC version of the archived files
+    pub archived_at: u64, // Unix timestamp (seconds)
+    pub record: Option<InstallRecord>, // None if installed outside the app
+}
+
+fn get_history_base_dir() -> PathBuf {
+    get_app_data_dir().join("versions")
+}
+
+fn get_history_dir(base_dir: &Path, flavor: GameFlavor, local_name: &str) -> PathBuf {
+    flavor.data_dir(base_dir).join(local_name)
+}
+
+/// Move versions archived before flavors existed into the retail folder
+pub fn migrate_legacy_versions() {
+    let Ok(entries) = fs::read_dir(get_history_base_dir()) else {
+        return;
+    };
+
+    let retail_dir = GameFlavor::Retail.data_dir(&get_history_base_dir());
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let is_flavor_dir = GameFlavor::ALL.iter().any(|flavor| flavor.dir_name() == name);
+        let dest = retail_dir.join(&name);
+        if entry.path().is_dir() && !is_flavor_dir && !dest.exists() && fs::create_dir_all(&retail_dir).is_ok() {
+            let _ = fs::rename(entry.path(), dest);
+        }
+    }
+}
+
+fn get_version_meta_path(dir: &Path) -> PathBuf {
+    dir.join("version.json")
+}
+
+fn get_version_files_path(dir: &Path) -> PathBuf {
+    dir.join("files")
+}
+
+/// Every archived version of an addon, newest first
+pub fn list_versions(flavor: GameFlavor, local_name: &str) -> Vec<AddonVersion> {
+    list_versions_in(&get_history_base_dir(), flavor, local_name)
+}
+
+fn list_versions_in(base_dir: &Path, flavor: GameFlavor, local_name: &str) -> Vec<AddonVersion> {
+    let mut versions = Vec::new();
+
+    if let Ok(entries) = fs::read_dir(get_history_dir(base_dir, flavor, local_name)) {
+        for entry in entries.flatten() {
+            let meta_path = get_version_meta_path(&entry.path());
+            if let Ok(content) = fs::read_to_string(&meta_path) {
+                if let Ok(version) = serde_json::from_str::<AddonVersion>(&content) {
+                    versions.push(version);
+                }
+            }
+        }
+    }
+
+    versions.sort_by(|a, b| b.archived_at.cmp(&a.archived_at).then_with(|| b.id.cmp(&a.id)));
+    versions
+}
+
+/// Copy the installed folder of an addon into the version cache
+pub fn archive_installed(flavor: GameFlavor, addon_path: &Path, local_name: &AddonFolderName) -> Result<(), String> {
+    if !addon_path.exists() {
+        return Ok(());
+    }
+
+    let record = install_record::load_record(flavor, local_name);
+    archive_installed_in(
+        &get_history_base_dir(),
+        flavor,
+        addon_path,
+        local_name,
+        record,
+        install_record::now_timestamp(),
+    )
+}
+
+fn archive_installed_in(
+    base_dir: &Path,
+    flavor: GameFlavor,
+    addon_path: &Path,
+    local_name: &AddonFolderName,
+    record: Option<InstallRecord>,
+    archived_at: u64,
+) -> Result<(), String> {
+    let revision = record
+        .as_ref()
+        .and_then(|r| r.tag.clone().or_else(|| r.commit_sha.clone()))
+        .map(|rev| rev.chars().take(40).collect::<String>())
+        .unwrap_or_else(|| "local".to_string());
+    // Tags may contain characters that are not valid in folder names
+    let revision: String = revision
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '.' || c == '-' { c } else { '_' })
+        .collect();
+    let id = format!("{}-{}", archived_at, revision);
+
+    let dir = get_history_dir(base_dir, flavor, local_name).join(&id);
+    if let Err(e) = copy_dir_all(addon_path, &get_version_files_path(&dir)) {
+        let _ = fs::remove_dir_all(&dir);
+        return Err(e);
+    }
+
+    let version = AddonVersion {
+        id,
+        local_name: local_name.clone(),
+        version: read_addon_version(addon_path, local_name, flavor).ok(),
+        archived_at,
+        record,
+    };
+
+    let json = serde_json::to_string_pretty(&version)
+        .map_err(|e| format!("Failed to serialize version metadata: {}", e))?;
+    fs::write(get_version_meta_path(&dir), json)
+        .map_err(|e| format!("Failed to write version metadata: {}", e))?;
+
+    Ok(())
+}
+
+/// Keep only the `retention` newest versions of an addon
+pub fn prune(flavor: GameFlavor, local_name: &str, retention: usize) {
+    prune_in(&get_history_base_dir(), flavor, local_name, retention);
+}
+
+fn prune_in(base_dir: &Path, flavor: GameFlavor, local_name: &str, retention: usize) {
+    for version in list_versions_in(base_dir, flavor, local_name).into_iter().skip(retention) {
+        let _ = fs::remove_dir_all(get_history_dir(base_dir, flavor, local_name).join(&version.id));
+    }
+}
+
+/// Restore an archived version into the AddOns folder
+pub fn restore_version(
+    flavor: GameFlavor,
+    addons_path: &Path,
+    local_name: &AddonFolderName,
+    version_id: &str,
+) -> Result<AddonVersion, String> {
+    let version = list_versions(flavor, local_name)
+        .into_iter()
+        .find(|v| v.id == version_id)
+        .ok_or_else(|| format!("Version '{}' of {} not found", version_id, local_name))?;
+
+    let files_path = get_version_files_path(&get_history_dir(&get_history_base_dir(), flavor, local_name).join(&version.id));
+    let retention = load_config().unwrap_or_default().version_retention;
+
+    // Keep the version being replaced so the rollback can itself be undone
+    if retention > 0 {
+        archive_installed(flavor, &addons_path.join(local_name), local_name)?;
+    }
+
+    let dest_path = swap::replace_addon_dir(&files_path, addons_path, flavor, local_name)?;
+
+    // Make the install record describe what is now on disk
+    match &version.record {
+        Some(record) => {
+            let restored = InstallRecord {
+                flavor,
+                installed_at: install_record::now_timestamp(),
+                files: install_record::hash_addon_files(&dest_path)?,
+                ..record.clone()
+            };
+            install_record::save_record(&restored)?;
+        }
+        None => install_record::remove_record(flavor, local_name)?,
+    }
+
+    prune(flavor, local_name, retention);
+
+    Ok(version)
+}
+
+/// List the previous versions of an addon kept in the local cache
+#[tauri::command]
+pub fn list_addon_versions(addon_name: AddonFolderName, flavor: GameFlavor) -> Result<Vec<AddonVersion>, String> {
+    Ok(list_versions(flavor, &addon_name))
+}
+
+/// Restore a previous version of an addon
+#[tauri::command]
+pub async fn rollback_addon(
+    app: tauri::AppHandle,
+    wow_path: String,
+    flavor: GameFlavor,
+    addon_name: AddonFolderName,
+    version_id: String,
+) -> Result<String, String> {
+    // Shares the staging folders and install record with installs
+    let _guard = app.state::<download::InstallRegistry>().begin(flavor, &addon_name)?;
+    let addons_path = flavor.addons_path(&wow_path);
+
+    // Archiving, copying and hashing would block the main thread
+    let local_name = addon_name.clone();
+    let version = tokio::task::spawn_blocking(move || {
+        restore_version(flavor, &addons_path, &local_name, &version_id)
+    })
+    .await
+    .map_err(|e| format!("Rollback of {} failed: {}", addon_name, e))??;
+
+    Ok(format!(
+        "Successfully rolled back {} to {}",
+        addon_name,
+        version.version.unwrap_or(version.id)
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::UpdateMode;
+
+    const ADDON: &str = "Nihui_uf";
+
+    fn write_addon(addon_path: &Path, version: &str) {
+        fs::create_dir_all(addon_path).unwrap();
+        fs::write(addon_path.join(format!("{}.toc", ADDON)), format!("## Version: {}\n", version)).unwrap();
+    }
+
+    fn record(tag: &str) -> InstallRecord {
+        InstallRecord {
+            local_name: AddonFolderName::from_static(ADDON),
+            flavor: GameFlavor::Retail,
+            source_url: "https://github.com/nihui/Nihui_uf".to_string(),
+            commit_sha: None,
+            tag: Some(tag.to_string()),
+            update_mode: UpdateMode::Release,
+            branch: None,
+            installed_at: 0,
+            files: Vec::new(),
+        }
+    }
+
+    /// Archive `version` of the addon as it would be before an update
+    fn archive(base_dir: &Path, addon_path: &Path, version: &str, archived_at: u64) {
+        write_addon(addon_path, version);
+        let name = AddonFolderName::from_static(ADDON);
+        let tag = format!("v{}", version);
+        archive_installed_in(base_dir, GameFlavor::Retail, addon_path, &name, Some(record(&tag)), archived_at).unwrap();
+    }
+
+    #[test]
+    fn archives_the_installed_files_and_metadata() {
+        let root = tempfile::tempdir().unwrap();
+        let base_dir = root.path().join("versions");
+        let addon_path = root.path().join("AddOns").join(ADDON);
+
+        archive(&base_dir, &addon_path, "1.0", 100);
+
+        let versions = list_versions_in(&base_dir, GameFlavor::Retail, ADDON);
+        assert_eq!(versions.len(), 1);
+        assert_eq!(versions[0].id, "100-v1.0");
+        assert_eq!(versions[0].version.as_deref(), Some("1.0"));
+        assert_eq!(versions[0].record.as_ref().and_then(|r| r.tag.as_deref()), Some("v1.0"));
+        let files = get_version_files_path(&get_history_dir(&base_dir, GameFlavor::Retail, ADDON).join("100-v1.0"));
+        assert!(files.join(format!("{}.toc", ADDON)).exists());
+    }
+
+    #[test]
+    fn lists_versions_newest_first() {
+        let root = tempfile::tempdir().unwrap();
+        let base_dir = root.path().join("versions");
+        let addon_path = root.path().join("AddOns").join(ADDON);
+
+        archive(&base_dir, &addon_path, "1.1", 200);
+        archive(&base_dir, &addon_path, "1.0", 100);
+        archive(&base_dir, &addon_path, "1.2", 300);
+
+        let ids: Vec<String> = list_versions_in(&base_dir, GameFlavor::Retail, ADDON)
+            .into_iter()
+            .map(|v| v.id)
+            .collect();
+        assert_eq!(ids, vec!["300-v1.2", "200-v1.1", "100-v1.0"]);
+        assert!(list_versions_in(&base_dir, GameFlavor::Classic, ADDON).is_empty());
+    }
+
+    #[test]
+    fn prunes_down_to_the_newest_versions() {
+        let root = tempfile::tempdir().unwrap();
+        let base_dir = root.path().join("versions");
+        let addon_path = root.path().join("AddOns").join(ADDON);
+        for (i, version) in ["1.0", "1.1", "1.2", "1.3"].iter().enumerate() {
+            archive(&base_dir, &addon_path, version, 100 * (i as u64 + 1));
+        }
+
+        prune_in(&base_dir, GameFlavor::Retail, ADDON, 2);
+
+        let ids: Vec<String> = list_versions_in(&base_dir, GameFlavor::Retail, ADDON)
+            .into_iter()
+            .map(|v| v.id)
+            .collect();
+        assert_eq!(ids, vec!["400-v1.3", "300-v1.2"]);
+        assert!(!get_history_dir(&base_dir, GameFlavor::Retail, ADDON).join("100-v1.0").exists());
+    }
+}
@@ -0,0 +1,630 @@
+use reqwest::header::{HeaderMap, ACCEPT, CACHE_CONTROL, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED, RETRY_AFTER};
+use reqwest::StatusCode;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::github_cache::{self, CachedResponse};
+use crate::secrets::{SecretStore, GITHUB_TOKEN_KEY};
+use crate::{load_config, AppConfig};
+
+// ===========================
+// SHARED HTTP CLIENT
+// ===========================
+
+/// Default connect and read timeout of every request
+pub const DEFAULT_HTTP_TIMEOUT_SECS: u64 = 30;
+
+const GITHUB_API_URL: &str = "https://api.github.com";
+const GITHUB_RAW_URL: &str = "https://raw.githubusercontent.com";
+const GITHUB_OAUTH_URL: &str = "https://github.com";
+
+/// Media type asking the contents API for the file itself instead of JSON
+const GITHUB_RAW_MEDIA_TYPE: &str = "application/vnd.github.raw+json";
+
+/// Below this many API requests left, background checks wait for the reset
+pub const LOW_RATE_LIMIT_REMAINING: u32 = 10;
+
+fn user_agent() -> String {
+    format!("Nihui-App/{}", env!("CARGO_PKG_VERSION"))
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// GitHub API quota, as reported by the last response
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+pub struct RateLimit {
+    pub limit: u32,
+    pub remaining: u32,
+    pub reset_at: u64, // Unix timestamp (seconds) when the quota is restored
+}
+
+impl RateLimit {
+    /// Read the X-RateLimit-* headers of a response
+    pub fn from_headers(headers: &HeaderMap) -> Option<RateLimit> {
+        let header = |name: &str| headers.get(name)?.to_str().ok()?.trim().parse::<u64>().ok();
+        Some(RateLimit {
+            limit: u32::try_from(header("x-ratelimit-limit")?).ok()?,
+            remaining: u32::try_from(header("x-ratelimit-remaining")?).ok()?,
+            reset_at: header("x-ratelimit-reset")?,
+        })
+    }
+
+    /// No request left before the reset
+    pub fn is_exhausted(&self, now: u64) -> bool {
+        self.remaining == 0 && self.reset_at > now
+    }
+
+    /// How long non-urgent requests should wait: until the reset when the
+    /// quota is nearly gone, zero otherwise
+    pub fn defer_delay(&self, now: u64) -> Duration {
+        if self.remaining <= LOW_RATE_LIMIT_REMAINING && self.reset_at > now {
+            Duration::from_secs(self.reset_at - now)
+        } else {
+            Duration::ZERO
+        }
+    }
+}
+
+/// Message of the error returned while the quota is exhausted
+pub fn rate_limit_message(reset_at: u64, now: u64) -> String {
+    let minutes = reset_at.saturating_sub(now).div_ceil(60).max(1);
+    format!("GitHub API rate limit exceeded, resets in {} min", minutes)
+}
+
+/// Body of a GitHub response, replayed from the cache on 304 Not Modified
+pub struct GitHubResponse {
+    pub status: StatusCode,
+    pub body: String,
+}
+
+/// HTTP client with the GitHub settings of the config.
+/// Cheap to clone: clones share the same connection pool.
+#[derive(Clone)]
+pub struct HttpClient {
+    client: reqwest::Client,
+    token: Option<String>, // Sent to GitHub only
+    rate_limit: Arc<Mutex<Option<RateLimit>>>, // Shared by every clone
+    api_url: String,
+    raw_url: String,
+    oauth_url: String,
+    cache_dir: Option<PathBuf>, // None = no disk cache, for authenticated clients
+    memory_cache: Arc<Mutex<HashMap<String, CachedResponse>>>, // Used instead of the disk cache
+}
+
+impl HttpClient {
+    pub fn new(config: &AppConfig, token: Option<String>) -> Result<Self, String> {
+        let timeout = Duration::from_secs(config.http_timeout_secs.max(1));
+
+        // Read timeout rather than a total one, so large downloads are not cut off
+        let client = reqwest::Client::builder()
+            .user_agent(user_agent())
+            .connect_timeout(timeout)
+            .read_timeout(timeout)
+            .build()
+            .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+        // A base URL override replaces every GitHub host, e.g. with a mock server
+        let trimmed = |url: &Option<String>| {
+            url.as_deref()
+                .map(|url| url.trim().trim_end_matches('/').to_string())
+                .filter(|url| !url.is_empty())
+        };
+        let base_url = trimmed(&config.github_base_url);
+        let url_or = |default: &str| base_url.clone().unwrap_or_else(|| default.to_string());
+
+        let token = token
+            .map(|token| token.trim().to_string())
+            .filter(|token| !token.is_empty());
+
+        Ok(HttpClient {
+            client,
+            // Responses to authenticated requests may come from private
+            // repositories, they are never written to disk
+            cache_dir: token.is_none().then(github_cache::cache_dir),
+            token,
+            rate_limit: Arc::default(),
+            memory_cache: Arc::default(),
+            api_url: url_or(GITHUB_API_URL),
+            raw_url: url_or(GITHUB_RAW_URL),
+            // The OAuth endpoints can also be pointed elsewhere on their own
+            oauth_url: trimmed(&config.github_oauth_url).unwrap_or_else(|| url_or(GITHUB_OAUTH_URL)),
+        })
+    }
+
+    /// Same client with its disk cache in `cache_dir`, away from the app data
+    #[cfg(test)]
+    pub fn with_cache_dir(mut self, cache_dir: &std::path::Path) -> Self {
+        self.cache_dir = Some(cache_dir.to_path_buf());
+        self
+    }
+
+    /// Whether requests to GitHub are authenticated
+    pub fn has_token(&self) -> bool {
+        self.token.is_some()
+    }
+
+    /// Request to any server, without credentials
+    pub fn get(&self, url: &str) -> reqwest::RequestBuilder {
+        self.client.get(url)
+    }
+
+    /// POST to any server, without credentials
+    pub fn post(&self, url: &str) -> reqwest::RequestBuilder {
+        self.client.post(url)
+    }
+
+    /// Request to GitHub, authenticated when a token is configured
+    pub fn github_get(&self, url: &str) -> reqwest::RequestBuilder {
+        let request = self.client.get(url);
+        match &self.token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+
+    /// Quota reported by the last GitHub API response
+    pub fn rate_limit(&self) -> Option<RateLimit> {
+        self.rate_limit.lock().ok().and_then(|rate_limit| *rate_limit)
+    }
+
+    /// Last successful response to `url`, from disk or, for authenticated
+    /// clients, from memory
+    fn cached_response(&self, url: &str) -> Option<CachedResponse> {
+        match &self.cache_dir {
+            Some(dir) => github_cache::load(dir, url),
+            None => self.memory_cache.lock().ok()?.get(url).cloned(),
+        }
+    }
+
+    fn cache_response(&self, entry: CachedResponse) {
+        match &self.cache_dir {
+            Some(dir) => {
+                let _ = github_cache::store(dir, &entry);
+            }
+            None => {
+                if let Ok(mut cache) = self.memory_cache.lock() {
+                    cache.insert(entry.url.clone(), entry);
+                }
+            }
+        }
+    }
+
+    fn record_rate_limit(&self, headers: &HeaderMap) {
+        if let (Some(rate_limit), Ok(mut current)) = (RateLimit::from_headers(headers), self.rate_limit.lock()) {
+            *current = Some(rate_limit);
+        }
+    }
+
+    /// GET a GitHub URL and read the body as text.
+    /// Sends the ETag/Last-Modified of the cached response so unchanged
+    /// resources come back as 304, which do not count against the quota.
+    /// Fails without a request while the API quota is exhausted.
+    pub async fn github_fetch(&self, url: &str) -> Result<GitHubResponse, String> {
+        self.github_fetch_as(url, None).await
+    }
+
+    /// Read a file of a repository at a branch, tag or commit. Goes through
+    /// the contents API when authenticated, so private repositories work too.
+    pub async fn github_file(&self, owner: &str, repo: &str, reference: &str, path: &str) -> Result<GitHubResponse, String> {
+        if self.has_token() {
+            let url = self.api_url(&format!("/repos/{}/{}/contents/{}?ref={}", owner, repo, path, reference));
+            self.github_fetch_as(&url, Some(GITHUB_RAW_MEDIA_TYPE)).await
+        } else {
+            self.github_fetch(&self.raw_url(owner, repo, reference, path)).await
+        }
+    }
+
+    async fn github_fetch_as(&self, url: &str, accept: Option<&str>) -> Result<GitHubResponse, String> {
+        let now = now_secs();
+        if url.starts_with(&self.api_url) {
+            if let Some(rate_limit) = self.rate_limit().filter(|r| r.is_exhausted(now)) {
+                return Err(rate_limit_message(rate_limit.reset_at, now));
+            }
+        }
+
+        let cached = self.cached_response(url);
+        let request = |cached: Option<&CachedResponse>| {
+            let mut request = self.github_get(url);
+            if let Some(accept) = accept {
+                request = request.header(ACCEPT, accept);
+            }
+            match cached {
+                Some(cached) => {
+                    if let Some(etag) = &cached.etag {
+                        request = request.header(IF_NONE_MATCH, etag);
+                    }
+                    if let Some(last_modified) = &cached.last_modified {
+                        request = request.header(IF_MODIFIED_SINCE, last_modified);
+                    }
+                }
+                // Nothing to replay a 304 with, so no cache on the way may answer one
+                None => request = request.header(CACHE_CONTROL, "no-cache"),
+            }
+            request
+        };
+
+        let mut response = request(cached.as_ref())
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        if response.status() == StatusCode::NOT_MODIFIED {
+            if let Some(cached) = cached {
+                self.record_rate_limit(response.headers());
+                return Ok(GitHubResponse {
+                    status: StatusCode::OK,
+                    body: cached.body,
+                });
+            }
+            // Nothing cached to replay: ask once more for the body instead of
+            // failing on the 304
+            response = request(None)
+                .send()
+                .await
+                .map_err(|e| e.to_string())?;
+        }
+
+        let headers = response.headers().clone();
+        self.record_rate_limit(&headers);
+        let status = response.status();
+
+        // 403 with no quota left, or 429 for the secondary limits
+        let rate_limited = status == StatusCode::TOO_MANY_REQUESTS
+            || (status == StatusCode::FORBIDDEN
+                && RateLimit::from_headers(&headers).is_some_and(|r| r.remaining == 0));
+        if rate_limited {
+            let retry_after = headers
+                .get(RETRY_AFTER)
+                .and_then(|value| value.to_str().ok()?.trim().parse::<u64>().ok());
+            let reset_at = match (retry_after, RateLimit::from_headers(&headers)) {
+                (Some(seconds), _) => now + seconds,
+                (None, Some(rate_limit)) => rate_limit.reset_at,
+                (None, None) => now + 60,
+            };
+            // Later requests fail fast until the reset, and callers can tell why
+            if let Ok(mut current) = self.rate_limit.lock() {
+                let limit = current.map(|r| r.limit).unwrap_or(0);
+                *current = Some(RateLimit {
+                    limit,
+                    remaining: 0,
+                    reset_at,
+                });
+            }
+            return Err(rate_limit_message(reset_at, now));
+        }
+
+        let body = response
+            .text()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        if status.is_success() {
+            let header = |name| headers.get(name).and_then(|v| v.to_str().ok()).map(str::to_string);
+            let entry = CachedResponse {
+                url: url.to_string(),
+                etag: header(ETAG),
+                last_modified: header(LAST_MODIFIED),
+                body: body.clone(),
+            };
+            if entry.etag.is_some() || entry.last_modified.is_some() {
+                self.cache_response(entry);
+            }
+        }
+
+        Ok(GitHubResponse { status, body })
+    }
+
+    /// Run a query against the GitHub GraphQL API, which requires a token.
+    /// Returns the whole response document, `data` and `errors` included.
+    pub async fn github_graphql(&self, query: &str) -> Result<serde_json::Value, String> {
+        let token = self.token.as_ref().ok_or("The GitHub GraphQL API requires a token")?;
+
+        let response = self
+            .client
+            .post(self.api_url("/graphql"))
+            .bearer_auth(token)
+            .json(&serde_json::json!({ "query": query }))
+            .send()
+            .await
+            .map_err(|e| format!("Failed to run GraphQL query: {}", e))?;
+
+        if !response.status().is_success() {
+            return Err(format!("GitHub GraphQL error: {}", response.status()));
+        }
+
+        response
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse GraphQL response: {}", e))
+    }
+
+    /// REST API URL, `path` starting with '/'
+    pub fn api_url(&self, path: &str) -> String {
+        format!("{}{}", self.api_url, path)
+    }
+
+    /// URL of a GitHub OAuth endpoint, `path` starting with '/'
+    pub fn oauth_url(&self, path: &str) -> String {
+        format!("{}{}", self.oauth_url, path)
+    }
+
+    /// URL of a file of a repository at a branch, tag or commit
+    pub fn raw_url(&self, owner: &str, repo: &str, reference: &str, file: &str) -> String {
+        format!("{}/{}/{}/{}/{}", self.raw_url, owner, repo, reference, file)
+    }
+
+    /// API URL of the ZIP archive of a repository at a branch, tag or commit.
+    /// Unlike github.com archive links, it accepts the token of private repositories.
+    pub fn zipball_url(&self, owner: &str, repo: &str, reference: &str) -> String {
+        self.api_url(&format!("/repos/{}/{}/zipball/{}", owner, repo, reference))
+    }
+
+    /// Error for an unsuccessful GitHub response about `what` (e.g. "repository
+    /// owner/repo"), telling a missing resource apart from a missing permission
+    pub fn status_error(&self, status: StatusCode, what: &str) -> String {
+        match status {
+            StatusCode::UNAUTHORIZED => "GitHub rejected the token, it may be invalid or expired".to_string(),
+            StatusCode::FORBIDDEN => format!("No access to {}: the GitHub token lacks permission", what),
+            // GitHub answers 404 for private repositories the caller cannot see
+            StatusCode::NOT_FOUND if self.has_token() => {
+                format!("Not found: {} does not exist or the GitHub token has no access to it", what)
+            }
+            StatusCode::NOT_FOUND => {
+                format!("Not found: {} does not exist, or is private and needs a GitHub token", what)
+            }
+            status => format!("GitHub API error for {}: {}", what, status),
+        }
+    }
+}
+
+/// Get the GitHub API quota reported by the last response, if any
+#[tauri::command]
+pub fn get_rate_limit(http: tauri::State<'_, SharedHttpClient>) -> Option<RateLimit> {
+    http.client().rate_limit()
+}
+
+/// Save the GitHub token in the secret store, or remove it with `None`
+#[tauri::command]
+pub fn set_github_token(token: Option<String>, http: tauri::State<'_, SharedHttpClient>) -> Result<(), String> {
+    http.set_token(token.as_deref())
+}
+
+/// Whether a GitHub token is saved
+#[tauri::command]
+pub fn has_github_token(http: tauri::State<'_, SharedHttpClient>) -> bool {
+    http.client().has_token()
+}
+
+/// The client kept in Tauri managed state, rebuilt when the config or the
+/// token changes. The token itself lives in the secret store.
+pub struct SharedHttpClient {
+    client: RwLock<HttpClient>,
+    secrets: Box<dyn SecretStore>,
+}
+
+impl SharedHttpClient {
+    pub fn new(config: &AppConfig, secrets: Box<dyn SecretStore>) -> Result<Self, String> {
+        let token = secrets.get(GITHUB_TOKEN_KEY).unwrap_or_default();
+        Ok(SharedHttpClient {
+            client: RwLock::new(HttpClient::new(config, token)?),
+            secrets,
+        })
+    }
+
+    pub fn client(&self) -> HttpClient {
+        match self.client.read() {
+            Ok(client) => client.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        }
+    }
+
+    /// Save the GitHub token, or remove it with `None`, and use it from now on.
+    /// Cached responses are dropped with the previous identity.
+    pub fn set_token(&self, token: Option<&str>) -> Result<(), String> {
+        match token.map(str::trim).filter(|token| !token.is_empty()) {
+            Some(token) => self.secrets.set(GITHUB_TOKEN_KEY, token)?,
+            None => self.secrets.delete(GITHUB_TOKEN_KEY)?,
+        }
+        github_cache::clear(&github_cache::cache_dir())?;
+        self.configure(&load_config().unwrap_or_default())
+    }
+
+    /// Apply new settings to every later request, keeping the known quota
+    pub fn configure(&self, config: &AppConfig) -> Result<(), String> {
+        let token = self.secrets.get(GITHUB_TOKEN_KEY)?;
+        let mut client = HttpClient::new(config, token)?;
+        let current = self.client();
+        client.rate_limit = current.rate_limit;
+        // Responses cached in memory belong to the token they were fetched with
+        if client.token == current.token {
+            client.memory_cache = current.memory_cache;
+        }
+        match self.client.write() {
+            Ok(mut current) => *current = client,
+            Err(poisoned) => *poisoned.into_inner() = client,
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stub_server::{StubResponse, StubServer};
+
+    #[test]
+    fn base_url_override_replaces_every_github_host() {
+        let config = AppConfig {
+            github_base_url: Some("http://127.0.0.1:8080/".to_string()),
+            ..AppConfig::default()
+        };
+        let http = HttpClient::new(&config, None).unwrap();
+
+        assert_eq!(http.api_url("/repos/o/r/branches"), "http://127.0.0.1:8080/repos/o/r/branches");
+        assert_eq!(http.raw_url("o", "r", "main", "A.toc"), "http://127.0.0.1:8080/o/r/main/A.toc");
+        assert_eq!(http.zipball_url("o", "r", "abc"), "http://127.0.0.1:8080/repos/o/r/zipball/abc");
+        assert_eq!(http.oauth_url("/login/device/code"), "http://127.0.0.1:8080/login/device/code");
+
+        let http = HttpClient::new(&AppConfig::default(), None).unwrap();
+        assert_eq!(http.api_url("/rate_limit"), "https://api.github.com/rate_limit");
+        assert_eq!(http.oauth_url("/login/device/code"), "https://github.com/login/device/code");
+    }
+
+    #[test]
+    fn oauth_url_override_only_moves_the_oauth_endpoints() {
+        let config = AppConfig {
+            github_oauth_url: Some("http://127.0.0.1:9090".to_string()),
+            ..AppConfig::default()
+        };
+        let http = HttpClient::new(&config, None).unwrap();
+
+        assert_eq!(http.oauth_url("/login/oauth/access_token"), "http://127.0.0.1:9090/login/oauth/access_token");
+        assert_eq!(http.api_url("/user"), "https://api.github.com/user");
+    }
+
+    #[test]
+    fn tells_missing_repositories_from_missing_access() {
+        let anonymous = HttpClient::new(&AppConfig::default(), None).unwrap();
+        let authenticated = HttpClient::new(&AppConfig::default(), Some("ghp_test".to_string())).unwrap();
+        let what = "repository o/r";
+
+        assert!(anonymous.status_error(StatusCode::NOT_FOUND, what).contains("needs a GitHub token"));
+        assert!(authenticated.status_error(StatusCode::NOT_FOUND, what).contains("has no access"));
+        assert!(authenticated.status_error(StatusCode::FORBIDDEN, what).starts_with("No access"));
+        assert!(authenticated.status_error(StatusCode::UNAUTHORIZED, what).contains("rejected the token"));
+    }
+
+    #[test]
+    fn reads_rate_limit_headers() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(RateLimit::from_headers(&headers), None);
+
+        headers.insert("x-ratelimit-limit", "60".parse().unwrap());
+        headers.insert("x-ratelimit-remaining", "5".parse().unwrap());
+        headers.insert("x-ratelimit-reset", "1700003600".parse().unwrap());
+        let rate_limit = RateLimit::from_headers(&headers).unwrap();
+
+        assert_eq!(rate_limit.remaining, 5);
+        assert!(!rate_limit.is_exhausted(1_700_000_000));
+        assert_eq!(rate_limit.defer_delay(1_700_000_000), Duration::from_secs(3600));
+        assert_eq!(rate_limit.defer_delay(1_700_003_600), Duration::ZERO);
+        assert_eq!(
+            rate_limit_message(rate_limit.reset_at, 1_700_003_000),
+            "GitHub API rate limit exceeded, resets in 10 min"
+        );
+    }
+
+    fn stub_client(server: &StubServer, cache_dir: &std::path::Path) -> HttpClient {
+        let config = AppConfig {
+            github_base_url: Some(server.url.clone()),
+            ..AppConfig::default()
+        };
+        HttpClient::new(&config, None).unwrap().with_cache_dir(cache_dir)
+    }
+
+    #[tokio::test]
+    async fn replays_the_cached_body_on_not_modified() {
+        let server = StubServer::start(vec![
+            StubResponse::json(serde_json::json!({ "tag_name": "v1.0.0" })).header("ETag", "\"abc\""),
+            StubResponse::status(304),
+        ])
+        .await;
+        let cache = tempfile::tempdir().unwrap();
+        let http = stub_client(&server, cache.path());
+        let url = http.api_url("/repos/o/r/releases/latest");
+
+        let first = http.github_fetch(&url).await.unwrap();
+        let second = http.github_fetch(&url).await.unwrap();
+
+        assert_eq!(second.status, StatusCode::OK);
+        assert_eq!(second.body, first.body);
+        let requests = server.requests();
+        assert_eq!(requests[0].header("if-none-match"), None);
+        assert_eq!(requests[1].header("if-none-match"), Some("\"abc\""));
+    }
+
+    #[tokio::test]
+    async fn asks_again_when_a_not_modified_has_nothing_to_replay() {
+        let server = StubServer::start(vec![
+            StubResponse::status(304),
+            StubResponse::json(serde_json::json!({ "tag_name": "v1.0.0" })),
+        ])
+        .await;
+        let cache = tempfile::tempdir().unwrap();
+        let http = stub_client(&server, cache.path());
+
+        let response = http.github_fetch(&http.api_url("/repos/o/r/releases/latest")).await.unwrap();
+
+        assert_eq!(response.status, StatusCode::OK);
+        assert!(response.body.contains("v1.0.0"));
+        let requests = server.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[1].header("if-none-match"), None);
+        assert_eq!(requests[1].header("cache-control"), Some("no-cache"));
+    }
+
+    #[tokio::test]
+    async fn authenticated_clients_cache_in_memory() {
+        let server = StubServer::start(vec![
+            StubResponse::json(serde_json::json!({ "tag_name": "v1.0.0" })).header("ETag", "\"abc\""),
+            StubResponse::status(304),
+        ])
+        .await;
+        let config = AppConfig {
+            github_base_url: Some(server.url.clone()),
+            ..AppConfig::default()
+        };
+        let http = HttpClient::new(&config, Some("ghp_test".to_string())).unwrap();
+        let url = http.api_url("/repos/o/r/releases/latest");
+
+        let first = http.github_fetch(&url).await.unwrap();
+        // Clones share the cache
+        let second = http.clone().github_fetch(&url).await.unwrap();
+
+        assert_eq!(second.status, StatusCode::OK);
+        assert_eq!(second.body, first.body);
+        assert_eq!(server.requests()[1].header("if-none-match"), Some("\"abc\""));
+    }
+
+    #[test]
+    fn authenticated_clients_do_not_cache_on_disk() {
+        let authenticated = HttpClient::new(&AppConfig::default(), Some("ghp_test".to_string())).unwrap();
+        assert!(authenticated.cache_dir.is_none());
+
+        let anonymous = HttpClient::new(&AppConfig::default(), None).unwrap();
+        assert!(anonymous.cache_dir.is_some());
+    }
+
+    #[tokio::test]
+    async fn waits_for_the_reset_once_the_quota_is_exhausted() {
+        let reset_at = now_secs() + 600;
+        let server = StubServer::start(vec![StubResponse::status(403)
+            .header("x-ratelimit-limit", "60")
+            .header("x-ratelimit-remaining", "0")
+            .header("x-ratelimit-reset", &reset_at.to_string())])
+        .await;
+        let cache = tempfile::tempdir().unwrap();
+        let http = stub_client(&server, cache.path());
+        let url = http.api_url("/repos/o/r/branches");
+
+        let error = http.github_fetch(&url).await.err().unwrap();
+        assert!(error.starts_with("GitHub API rate limit exceeded"));
+
+        // Fails fast without another request, and background checks wait
+        let error = http.github_fetch(&url).await.err().unwrap();
+        assert!(error.starts_with("GitHub API rate limit exceeded"));
+        assert_eq!(server.requests().len(), 1);
+
+        let rate_limit = http.rate_limit().unwrap();
+        assert!(rate_limit.is_exhausted(now_secs()));
+        assert!(rate_limit.defer_delay(now_secs()) > Duration::from_secs(500));
+    }
+}
@@ -0,0 +1,269 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::addon_name::AddonFolderName;
+use crate::flavor::GameFlavor;
+use crate::{get_app_data_dir, UpdateMode};
+
+// ===========================
+// INSTALL RECORDS
+// ===========================
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct InstalledFile {
+    pub path: String, // Relative to the addon folder, '/' separated
+    pub sha256: String,
+}
+
+/// How a recorded file compares with the one on disk
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FileState {
+    Unchanged,
+    Modified,
+    Missing,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ChangedFile {
+    pub path: String,
+    pub state: FileState,
+}
+
+/// What was installed for an addon, and from where
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct InstallRecord {
+    pub local_name: AddonFolderName,
+    #[serde(default)]
+    pub flavor: GameFlavor,
+    pub source_url: String,
+    pub commit_sha: Option<String>,
+    pub tag: Option<String>, // Release tag, release mode only
+    pub update_mode: UpdateMode,
+    pub branch: Option<String>,
+    pub installed_at: u64, // Unix timestamp (seconds)
+    pub files: Vec<InstalledFile>,
+}
+
+fn get_records_base_dir() -> PathBuf {
+    get_app_data_dir().join("installs")
+}
+
+fn get_records_dir(flavor: GameFlavor) -> PathBuf {
+    flavor.data_dir(&get_records_base_dir())
+}
+
+fn get_record_path(flavor: GameFlavor, local_name: &str) -> PathBuf {
+    get_records_dir(flavor).join(format!("{}.json", local_name))
+}
+
+/// Move records written before flavors existed into the retail folder
+pub fn migrate_legacy_records() {
+    let Ok(entries) = fs::read_dir(get_records_base_dir()) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_file() && path.extension().is_some_and(|ext| ext == "json") {
+            let dest = get_records_dir(GameFlavor::Retail).join(entry.file_name());
+            if fs::create_dir_all(get_records_dir(GameFlavor::Retail)).is_ok() && !dest.exists() {
+                let _ = fs::rename(&path, &dest);
+            }
+        }
+    }
+}
+
+pub fn now_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Read the install record of an addon, if any
+pub fn load_record(flavor: GameFlavor, local_name: &str) -> Option<InstallRecord> {
+    let content = fs::read_to_string(get_record_path(flavor, local_name)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Read every install record of a flavor
+pub fn load_all_records(flavor: GameFlavor) -> Vec<InstallRecord> {
+    let mut records = Vec::new();
+
+    if let Ok(entries) = fs::read_dir(get_records_dir(flavor)) {
+        for entry in entries.flatten() {
+            if entry.path().extension().is_some_and(|ext| ext == "json") {
+                if let Ok(content) = fs::read_to_string(entry.path()) {
+                    if let Ok(record) = serde_json::from_str::<InstallRecord>(&content) {
+                        records.push(record);
+                    }
+                }
+            }
+        }
+    }
+
+    records.sort_by(|a, b| a.local_name.cmp(&b.local_name));
+    records
+}
+
+pub fn save_record(record: &InstallRecord) -> Result<(), String> {
+    let records_dir = get_records_dir(record.flavor);
+    fs::create_dir_all(&records_dir)
+        .map_err(|e| format!("Failed to create install records directory: {}", e))?;
+
+    let json = serde_json::to_string_pretty(record)
+        .map_err(|e| format!("Failed to serialize install record: {}", e))?;
+
+    fs::write(get_record_path(record.flavor, &record.local_name), json)
+        .map_err(|e| format!("Failed to write install record: {}", e))?;
+
+    Ok(())
+}
+
+pub fn remove_record(flavor: GameFlavor, local_name: &str) -> Result<(), String> {
+    let path = get_record_path(flavor, local_name);
+    if path.exists() {
+        fs::remove_file(&path)
+            .map_err(|e| format!("Failed to remove install record: {}", e))?;
+    }
+    Ok(())
+}
+
+/// SHA-256 of a single file, as lowercase hex
+pub fn hash_file(path: &Path) -> Result<String, String> {
+    let mut file = fs::File::open(path)
+        .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher)
+        .map_err(|e| format!("Failed to hash {}: {}", path.display(), e))?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Hash every file below `root`, sorted by relative path
+pub fn hash_addon_files(root: &Path) -> Result<Vec<InstalledFile>, String> {
+    let mut files = Vec::new();
+    collect_file_hashes(root, root, &mut files)?;
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(files)
+}
+
+fn collect_file_hashes(root: &Path, dir: &Path, files: &mut Vec<InstalledFile>) -> Result<(), String> {
+    for entry in fs::read_dir(dir)
+        .map_err(|e| format!("Failed to read directory: {}", e))? {
+        let entry = entry.map_err(|e| format!("Failed to read directory entry: {}", e))?;
+        let path = entry.path();
+
+        if path.is_dir() {
+            collect_file_hashes(root, &path, files)?;
+        } else {
+            let relative = path
+                .strip_prefix(root)
+                .map_err(|e| format!("Failed to resolve relative path: {}", e))?
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy().to_string())
+                .collect::<Vec<_>>()
+                .join("/");
+
+            files.push(InstalledFile {
+                path: relative,
+                sha256: hash_file(&path)?,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Compare a recorded file with what is on disk below `root`
+pub fn file_state(root: &Path, file: &InstalledFile) -> FileState {
+    let path = file.path.split('/').fold(root.to_path_buf(), |path, part| path.join(part));
+    if !path.is_file() {
+        return FileState::Missing;
+    }
+
+    match hash_file(&path) {
+        Ok(sha256) if sha256 == file.sha256 => FileState::Unchanged,
+        _ => FileState::Modified,
+    }
+}
+
+/// Recorded files that were edited or deleted since the install
+pub fn changed_files(root: &Path, files: &[InstalledFile]) -> Vec<ChangedFile> {
+    files
+        .iter()
+        .filter_map(|file| match file_state(root, file) {
+            FileState::Unchanged => None,
+            state => Some(ChangedFile { path: file.path.clone(), state }),
+        })
+        .collect()
+}
+
+/// Get the install record of an addon
+#[tauri::command]
+pub fn get_install_record(addon_name: AddonFolderName, flavor: GameFlavor) -> Result<Option<InstallRecord>, String> {
+    Ok(load_record(flavor, &addon_name))
+}
+
+/// Get the install records of every addon installed through the app
+#[tauri::command]
+pub fn get_install_records(flavor: GameFlavor) -> Result<Vec<InstallRecord>, String> {
+    Ok(load_all_records(flavor))
+}
+
+/// Get the files of an addon that no longer match its install record
+#[tauri::command]
+pub fn get_changed_files(wow_path: String, addon_name: AddonFolderName, flavor: GameFlavor) -> Result<Vec<ChangedFile>, String> {
+    let record = load_record(flavor, &addon_name)
+        .ok_or_else(|| format!("{} was not installed through the app", addon_name))?;
+    Ok(changed_files(&flavor.addons_path(&wow_path).join(&addon_name), &record.files))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn addon_dir() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("Libs")).unwrap();
+        fs::write(dir.path().join("Nihui_uf.toc"), "## Version: 1.0\n").unwrap();
+        fs::write(dir.path().join("core.lua"), "print('hi')\n").unwrap();
+        fs::write(dir.path().join("Libs").join("embeds.xml"), "<Ui/>\n").unwrap();
+        dir
+    }
+
+    #[test]
+    fn hashes_every_file_by_relative_path() {
+        let dir = addon_dir();
+
+        let files = hash_addon_files(dir.path()).unwrap();
+
+        let paths: Vec<&str> = files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, vec!["Libs/embeds.xml", "Nihui_uf.toc", "core.lua"]);
+        assert_eq!(files[2].sha256, "caf026f25d7140209f98072605307a438914b9ce6f3c14b23d15d9667241de52");
+    }
+
+    #[test]
+    fn unchanged_files_are_not_reported() {
+        let dir = addon_dir();
+        let files = hash_addon_files(dir.path()).unwrap();
+
+        assert!(files.iter().all(|f| file_state(dir.path(), f) == FileState::Unchanged));
+        assert!(changed_files(dir.path(), &files).is_empty());
+    }
+
+    #[test]
+    fn reports_modified_and_missing_files() {
+        let dir = addon_dir();
+        let files = hash_addon_files(dir.path()).unwrap();
+
+        fs::write(dir.path().join("core.lua"), "print('edited')\n").unwrap();
+        fs::remove_file(dir.path().join("Libs").join("embeds.xml")).unwrap();
+
+        let changed = changed_files(dir.path(), &files);
+        let states: Vec<(&str, FileState)> = changed.iter().map(|f| (f.path.as_str(), f.state)).collect();
+        assert_eq!(states, vec![("Libs/embeds.xml", FileState::Missing), ("core.lua", FileState::Modified)]);
+    }
+}
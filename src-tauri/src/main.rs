@@ -5,10 +5,39 @@ use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::collections::HashMap;
-use regex::Regex;
-use zip::ZipArchive;
+use std::sync::Mutex;
 use tauri::Manager;
 
+use addon_name::AddonFolderName;
+use flavor::GameFlavor;
+use http::{HttpClient, SharedHttpClient};
+
+mod addon_name;
+mod build_info;
+mod catalog;
+mod download;
+mod flavor;
+mod github_cache;
+mod graphql;
+mod history;
+mod http;
+mod install_record;
+mod oauth;
+#[cfg_attr(not(any(target_os = "windows", target_os = "linux")), allow(dead_code))] // Battle.net is scanned on Windows and Linux
+mod product_db;
+mod quarantine;
+mod saved_variables;
+mod scheduler;
+mod secrets;
+#[cfg(test)]
+mod stub_server;
+mod swap;
+mod toc;
+mod update_check;
+mod version;
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))] // Wine prefixes are only scanned on Linux
+mod wine;
+
 // ===========================
 // TYPES & STRUCTS
 // ===========================
@@ -30,6 +59,79 @@ pub struct AppConfig {
     pub language: String,
     #[serde(default)]
     pub addon_overrides: HashMap<String, AddonOverride>,
+    #[serde(default)]
+    pub flavor_overrides: HashMap<GameFlavor, HashMap<String, AddonOverride>>, // Applied over addon_overrides
+    #[serde(default)]
+    pub active_flavor: GameFlavor, // Flavor shown in the app
+    #[serde(default)]
+    pub catalog_url: Option<String>, // None = use the default remote catalog
+    #[serde(default)]
+    pub custom_addons: Vec<AddonDefinition>,
+    #[serde(default = "default_check_concurrency")]
+    pub check_concurrency: usize,
+    #[serde(default = "default_check_interval_minutes")]
+    pub check_interval_minutes: u64, // 0 = background checks disabled
+    #[serde(default = "default_version_retention")]
+    pub version_retention: usize, // Previous versions kept per addon, 0 = none
+    #[serde(default = "default_quarantine_days")]
+    pub quarantine_days: u64, // Days uninstalled addons stay restorable, 0 = forever
+    #[serde(default)]
+    pub last_seen_builds: HashMap<GameFlavor, u32>, // Client build of each flavor at the last patch check
+    #[serde(default, skip_serializing)]
+    pub github_token: Option<String>, // Legacy plain-text token, moved to the secret store on startup
+    #[serde(default = "default_http_timeout_secs")]
+    pub http_timeout_secs: u64, // Connect and read timeout of HTTP requests
+    #[serde(default)]
+    pub github_base_url: Option<String>, // Replaces the GitHub hosts, e.g. with a mock server
+    #[serde(default)]
+    pub github_oauth_url: Option<String>, // Replaces the host of the GitHub login endpoints
+    #[serde(default)]
+    pub github_client_id: Option<String>, // OAuth app used to sign in, None = the built-in one
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        AppConfig {
+            wow_path: None,
+            launch_on_startup: true,
+            minimize_on_startup: true,
+            language: "en".to_string(),
+            addon_overrides: HashMap::new(),
+            flavor_overrides: HashMap::new(),
+            active_flavor: GameFlavor::default(),
+            catalog_url: None,
+            custom_addons: Vec::new(),
+            check_concurrency: default_check_concurrency(),
+            check_interval_minutes: default_check_interval_minutes(),
+            version_retention: default_version_retention(),
+            quarantine_days: default_quarantine_days(),
+            last_seen_builds: HashMap::new(),
+            github_token: None,
+            http_timeout_secs: default_http_timeout_secs(),
+            github_base_url: None,
+            github_oauth_url: None,
+            github_client_id: None,
+        }
+    }
+}
+
+impl AppConfig {
+    /// URL of the addon catalog manifest to use
+    pub fn catalog_url(&self) -> &str {
+        self.catalog_url
+            .as_deref()
+            .filter(|url| !url.trim().is_empty())
+            .unwrap_or(catalog::DEFAULT_CATALOG_URL)
+    }
+
+    /// Take the fields only backend commands change from `current`, since
+    /// the frontend copy of the config may be older than them
+    fn keep_backend_fields(&mut self, current: &mut AppConfig) {
+        self.addon_overrides = std::mem::take(&mut current.addon_overrides);
+        self.flavor_overrides = std::mem::take(&mut current.flavor_overrides);
+        self.custom_addons = std::mem::take(&mut current.custom_addons);
+        self.last_seen_builds = std::mem::take(&mut current.last_seen_builds);
+    }
 }
 
 fn default_launch_on_startup() -> bool {
@@ -44,6 +146,26 @@ fn default_language() -> String {
     "".to_string() // Empty string = auto-detect system language in frontend
 }
 
+fn default_check_concurrency() -> usize {
+    update_check::DEFAULT_CHECK_CONCURRENCY
+}
+
+fn default_check_interval_minutes() -> u64 {
+    scheduler::DEFAULT_CHECK_INTERVAL_MINUTES
+}
+
+fn default_version_retention() -> usize {
+    history::DEFAULT_VERSION_RETENTION
+}
+
+fn default_quarantine_days() -> u64 {
+    quarantine::DEFAULT_QUARANTINE_DAYS
+}
+
+fn default_http_timeout_secs() -> u64 {
+    http::DEFAULT_HTTP_TIMEOUT_SECS
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum UpdateMode {
@@ -53,7 +175,7 @@ pub enum UpdateMode {
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct AddonDefinition {
-    pub local_name: String,
+    pub local_name: AddonFolderName,
     pub nice_name: String,
     pub github_owner: String,
     pub github_repo: String,
@@ -62,85 +184,6 @@ pub struct AddonDefinition {
     pub branch: Option<String>, // Branch name for branch mode
 }
 
-// Hardcoded list of Nihui addons
-fn get_addon_definitions() -> Vec<AddonDefinition> {
-    vec![
-        AddonDefinition {
-            local_name: "Nihui_uf".to_string(),
-            nice_name: "Unit Frames".to_string(),
-            github_owner: "Nihilop".to_string(),
-            github_repo: "Nihui_unitframe".to_string(),
-            description: "Unit frames addon".to_string(),
-            update_mode: UpdateMode::Branch,
-            branch: Some("main".to_string()),
-        },
-        AddonDefinition {
-            local_name: "Nihui_ab".to_string(),
-            nice_name: "Action Bars".to_string(),
-            github_owner: "Nihilop".to_string(),
-            github_repo: "Nihui_actionbars".to_string(),
-            description: "Action bars addon".to_string(),
-            update_mode: UpdateMode::Branch,
-            branch: Some("main".to_string()),
-        },
-        AddonDefinition {
-            local_name: "Nihui_iv".to_string(),
-            nice_name: "Inventory".to_string(),
-            github_owner: "Nihilop".to_string(),
-            github_repo: "Nihui_inventory".to_string(),
-            description: "Inventory addon".to_string(),
-            update_mode: UpdateMode::Branch,
-            branch: Some("main".to_string()),
-        },
-        AddonDefinition {
-            local_name: "Nihui_cb".to_string(),
-            nice_name: "Cast Bars".to_string(),
-            github_owner: "Nihilop".to_string(),
-            github_repo: "Nihui_castbars".to_string(),
-            description: "Castbars addon".to_string(),
-            update_mode: UpdateMode::Branch,
-            branch: Some("main".to_string()),
-        },
-        AddonDefinition {
-            local_name: "Nihui_np".to_string(),
-            nice_name: "Nameplates".to_string(),
-            github_owner: "Nihilop".to_string(),
-            github_repo: "Nihui_nameplate".to_string(),
-            description: "Nameplate addon".to_string(),
-            update_mode: UpdateMode::Branch,
-            branch: Some("main".to_string()),
-        },
-        AddonDefinition {
-            local_name: "Nihui_chat".to_string(),
-            nice_name: "Nihui Chatbox".to_string(),
-            github_owner: "Nihilop".to_string(),
-            github_repo: "Nihui_chat".to_string(),
-            description: "Chatbox addon".to_string(),
-            update_mode: UpdateMode::Branch,
-            branch: Some("main".to_string()),
-        },
-        AddonDefinition {
-            local_name: "WaypointUI".to_string(),
-            nice_name: "Waypoint UI".to_string(),
-            github_owner: "Adaptvx".to_string(),
-            github_repo: "Waypoint-UI".to_string(),
-            description: "waypoint addon".to_string(),
-            update_mode: UpdateMode::Branch,
-            branch: Some("main".to_string()),
-        },
-        // Add more addons here as needed
-        // Example with branch mode:
-        // AddonDefinition {
-        //     local_name: "Nihui_nameplate".to_string(),
-        //     github_owner: "Nihilop".to_string(),
-        //     github_repo: "Nihui_nameplate".to_string(),
-        //     description: "Nameplate addon".to_string(),
-        //     update_mode: UpdateMode::Branch,
-        //     branch: Some("dev".to_string()),
-        // },
-    ]
-}
-
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct AddonInfo {
     pub name: String,
@@ -148,6 +191,12 @@ pub struct AddonInfo {
     pub path: String,
 }
 
+#[derive(Debug, Serialize, Clone)]
+pub struct WowPathValidation {
+    pub valid: bool,
+    pub flavors: Vec<GameFlavor>, // Flavors with an Interface/AddOns folder
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct GitHubRelease {
     pub tag_name: String,
@@ -160,7 +209,7 @@ pub struct GitHubRelease {
 // WOW PATH SCANNER
 // ===========================
 
-/// Auto-scan for WoW installation paths on Windows
+/// Auto-scan for WoW installation paths on Windows, and in Wine prefixes on Linux
 #[tauri::command]
 fn find_wow_path() -> Result<Vec<String>, String> {
     let mut paths = Vec::new();
@@ -169,36 +218,29 @@ fn find_wow_path() -> Result<Vec<String>, String> {
     #[cfg(target_os = "windows")]
     {
         // Battle.net product.db is in C:\ProgramData\Battle.net\Agent\product.db
-        let product_db = PathBuf::from("C:\\ProgramData\\Battle.net\\Agent\\product.db");
-
-        if product_db.exists() {
-            // Read as bytes since it's a binary file
-            if let Ok(bytes) = fs::read(&product_db) {
-                // Convert bytes to string, replacing invalid UTF-8 with replacement char
-                let content = String::from_utf8_lossy(&bytes);
-
-                // Extract Windows paths that contain "World of Warcraft"
-                // Looking for patterns like "E:/Battle.net/World of Warcraft"
-                let re = Regex::new(r"([A-Z]:[/\\][^\x00-\x1F]*World of Warcraft)").unwrap();
-                for cap in re.captures_iter(&content) {
-                    if let Some(install_path) = cap.get(1) {
-                        let mut path_str = install_path.as_str().to_string();
-
-                        // Clean up the path - remove any trailing garbage characters
-                        if let Some(pos) = path_str.find(|c: char| c < ' ') {
-                            path_str = path_str[..pos].to_string();
-                        }
+        let product_db_path = PathBuf::from("C:\\ProgramData\\Battle.net\\Agent\\product.db");
 
-                        // Normalize path separators to backslash for Windows
-                        path_str = path_str.replace("/", "\\");
+        if let Ok(installs) = product_db::read_product_db(&product_db_path) {
+            for install in installs.iter().filter(|install| install.is_wow()) {
+                // Normalize path separators to backslash for Windows
+                let path_str = install.install_path.replace('/', "\\");
 
-                        let path = PathBuf::from(&path_str);
-                        if validate_wow_path_internal(&path) {
-                            if !paths.contains(&path_str) {
-                                paths.push(path_str);
-                            }
-                        }
-                    }
+                let path = PathBuf::from(&path_str);
+                if !flavor::detect_flavors(&path).is_empty() && !paths.contains(&path_str) {
+                    paths.push(path_str);
+                }
+            }
+        }
+    }
+
+    // Wine, Lutris, Bottles and Proton prefixes
+    #[cfg(target_os = "linux")]
+    {
+        if let Some(home) = dirs::home_dir() {
+            for path in wine::find_installs(&home) {
+                let path_str = path.to_string_lossy().to_string();
+                if !paths.contains(&path_str) {
+                    paths.push(path_str);
                 }
             }
         }
@@ -211,20 +253,14 @@ fn find_wow_path() -> Result<Vec<String>, String> {
     }
 }
 
-/// Validate if a path is a valid WoW _retail_ directory
+/// Validate a WoW directory and report which game flavors it contains
 #[tauri::command]
-fn validate_wow_path(path: String) -> Result<bool, String> {
-    let path_buf = PathBuf::from(&path);
-    Ok(validate_wow_path_internal(&path_buf))
-}
-
-fn validate_wow_path_internal(path: &Path) -> bool {
-    // Check if _retail_/Interface/AddOns exists
-    let retail_path = path.join("_retail_");
-    let interface_path = retail_path.join("Interface");
-    let addons_path = interface_path.join("AddOns");
-
-    retail_path.exists() && interface_path.exists() && addons_path.exists()
+fn validate_wow_path(path: String) -> Result<WowPathValidation, String> {
+    let flavors = flavor::detect_flavors(&PathBuf::from(&path));
+    Ok(WowPathValidation {
+        valid: !flavors.is_empty(),
+        flavors,
+    })
 }
 
 // ===========================
@@ -233,11 +269,8 @@ fn validate_wow_path_internal(path: &Path) -> bool {
 
 /// Read addon versions from local installation (based on addon definitions)
 #[tauri::command]
-fn get_local_addon_versions(wow_path: String) -> Result<Vec<AddonInfo>, String> {
-    let addons_path = PathBuf::from(&wow_path)
-        .join("_retail_")
-        .join("Interface")
-        .join("AddOns");
+fn get_local_addon_versions(wow_path: String, flavor: GameFlavor) -> Result<Vec<AddonInfo>, String> {
+    let addons_path = flavor.addons_path(&wow_path);
 
     if !addons_path.exists() {
         return Err("AddOns directory not found".to_string());
@@ -246,9 +279,10 @@ fn get_local_addon_versions(wow_path: String) -> Result<Vec<AddonInfo>, String>
     let mut addons = Vec::new();
 
     // Get list of addons to scan from definitions
-    let addon_definitions = get_addon_definitions();
+    let config = load_config().unwrap_or_default();
+    let addon_definitions = resolve_addon_list(&config, flavor);
     let addon_names: Vec<String> = addon_definitions.iter()
-        .map(|def| def.local_name.clone())
+        .map(|def| def.local_name.to_string())
         .collect();
 
     // Read all directories
@@ -260,9 +294,7 @@ fn get_local_addon_versions(wow_path: String) -> Result<Vec<AddonInfo>, String>
 
                     // Check if this directory matches any addon in our definitions
                     if addon_names.contains(&dir_name) {
-                        let toc_path = entry.path().join(format!("{}.toc", dir_name));
-
-                        if let Ok(version) = read_version_from_toc(&toc_path) {
+                        if let Ok(version) = read_addon_version(&entry.path(), &dir_name, flavor) {
                             addons.push(AddonInfo {
                                 name: dir_name.clone(),
                                 version,
@@ -287,23 +319,16 @@ fn get_local_addon_versions(wow_path: String) -> Result<Vec<AddonInfo>, String>
 
 /// Read version from a .toc file
 fn read_version_from_toc(toc_path: &Path) -> Result<String, String> {
-    if !toc_path.exists() {
-        return Err("TOC file not found".to_string());
-    }
-
-    let content = fs::read_to_string(toc_path)
-        .map_err(|e| format!("Failed to read TOC file: {}", e))?;
-
-    // Look for ## Version: line
-    let re = Regex::new(r"(?m)^##\s*Version:\s*(.+)$").unwrap();
-
-    if let Some(cap) = re.captures(&content) {
-        if let Some(version) = cap.get(1) {
-            return Ok(version.as_str().trim().to_string());
-        }
-    }
+    toc::read_toc(toc_path)?
+        .version
+        .ok_or_else(|| "Version not found in TOC file".to_string())
+}
 
-    Err("Version not found in TOC file".to_string())
+/// Read the version of an installed addon from the TOC the game would load
+fn read_addon_version(addon_path: &Path, addon_name: &str, flavor: GameFlavor) -> Result<String, String> {
+    let toc_path = toc::find_toc(addon_path, addon_name, flavor.toc_client())
+        .ok_or("TOC file not found")?;
+    read_version_from_toc(&toc_path)
 }
 
 // ===========================
@@ -315,9 +340,45 @@ fn get_config_path() -> PathBuf {
     app_data.join("nihui_app").join("config.json")
 }
 
+/// Directory for app-managed data (caches, records, backups)
+pub fn get_app_data_dir() -> PathBuf {
+    let app_data = dirs::data_dir().unwrap_or_else(|| PathBuf::from("."));
+    app_data.join("nihui_app")
+}
+
 /// Save app configuration
 #[tauri::command]
-fn save_config(config: AppConfig, app: tauri::AppHandle) -> Result<(), String> {
+fn save_config(mut config: AppConfig, app: tauri::AppHandle) -> Result<(), String> {
+    let config = update_config(|current| {
+        config.keep_backend_fields(current);
+        *current = config.clone();
+        Ok(config)
+    })?;
+
+    // New token or timeouts apply to the next requests
+    app.state::<SharedHttpClient>().configure(&config)?;
+
+    // Apply autostart setting
+    apply_autostart_setting(config.launch_on_startup, &app)?;
+
+    Ok(())
+}
+
+/// Serializes read-modify-write cycles of the config file
+static CONFIG_LOCK: Mutex<()> = Mutex::new(());
+
+/// Load the config, change it and write it back, without another update
+/// landing in between. A config that fails to parse is left untouched.
+fn update_config<T>(change: impl FnOnce(&mut AppConfig) -> Result<T, String>) -> Result<T, String> {
+    let _lock = CONFIG_LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let mut config = load_config()?;
+    let result = change(&mut config)?;
+    write_config(&config)?;
+    Ok(result)
+}
+
+/// Write configuration to disk
+fn write_config(config: &AppConfig) -> Result<(), String> {
     let config_path = get_config_path();
 
     // Create parent directory if it doesn't exist
@@ -326,15 +387,12 @@ fn save_config(config: AppConfig, app: tauri::AppHandle) -> Result<(), String> {
             .map_err(|e| format!("Failed to create config directory: {}", e))?;
     }
 
-    let json = serde_json::to_string_pretty(&config)
+    let json = serde_json::to_string_pretty(config)
         .map_err(|e| format!("Failed to serialize config: {}", e))?;
 
     fs::write(&config_path, json)
         .map_err(|e| format!("Failed to write config file: {}", e))?;
 
-    // Apply autostart setting
-    apply_autostart_setting(config.launch_on_startup, &app)?;
-
     Ok(())
 }
 
@@ -362,13 +420,7 @@ fn load_config() -> Result<AppConfig, String> {
 
     if !config_path.exists() {
         // Return default config
-        return Ok(AppConfig {
-            wow_path: None,
-            launch_on_startup: true,
-            minimize_on_startup: true,
-            language: "en".to_string(),
-            addon_overrides: HashMap::new(),
-        });
+        return Ok(AppConfig::default());
     }
 
     let content = fs::read_to_string(&config_path)
@@ -380,100 +432,235 @@ fn load_config() -> Result<AppConfig, String> {
     Ok(config)
 }
 
-/// Get the hardcoded list of addon definitions with overrides applied
-#[tauri::command]
-fn get_addon_list() -> Result<Vec<AddonDefinition>, String> {
-    let mut addons = get_addon_definitions();
-
-    // Load config to get overrides
-    if let Ok(config) = load_config() {
-        // Apply overrides to each addon
-        for addon in &mut addons {
-            if let Some(override_config) = config.addon_overrides.get(&addon.local_name) {
-                // Apply update_mode override if present
-                if let Some(update_mode) = &override_config.update_mode {
-                    addon.update_mode = update_mode.clone();
-                }
-                // Apply branch override if present
-                if let Some(branch) = &override_config.branch {
-                    addon.branch = Some(branch.clone());
-                }
+/// Resolve the addon list of a flavor from the catalog and custom addons,
+/// with config overrides applied
+fn resolve_addon_list(config: &AppConfig, flavor: GameFlavor) -> Vec<AddonDefinition> {
+    let mut addons = catalog::load_addon_definitions();
+
+    // Custom addons cannot shadow catalog entries
+    for custom in &config.custom_addons {
+        if !addons.iter().any(|def| def.local_name.eq_ignore_ascii_case(&custom.local_name)) {
+            addons.push(custom.clone());
+        }
+    }
+
+    // Apply overrides to each addon, flavor-specific ones last
+    let flavor_overrides = config.flavor_overrides.get(&flavor);
+    for addon in &mut addons {
+        let overrides = [
+            config.addon_overrides.get(addon.local_name.as_str()),
+            flavor_overrides.and_then(|overrides| overrides.get(addon.local_name.as_str())),
+        ];
+        for override_config in overrides.into_iter().flatten() {
+            // Apply update_mode override if present
+            if let Some(update_mode) = &override_config.update_mode {
+                addon.update_mode = update_mode.clone();
+            }
+            // Apply branch override if present
+            if let Some(branch) = &override_config.branch {
+                addon.branch = Some(branch.clone());
             }
         }
     }
 
-    Ok(addons)
+    addons
+}
+
+/// Get the addon catalog with overrides applied.
+/// The remote manifest is refreshed when the cache is stale; on failure the
+/// cached or built-in catalog is served instead.
+#[tauri::command]
+async fn get_addon_list(
+    flavor: GameFlavor,
+    http: tauri::State<'_, SharedHttpClient>,
+) -> Result<Vec<AddonDefinition>, String> {
+    let config = load_config().unwrap_or_default();
+
+    // Offline or invalid manifest: keep serving what we have
+    let _ = catalog::refresh_catalog(&http.client(), config.catalog_url(), false).await;
+
+    Ok(resolve_addon_list(&config, flavor))
 }
 
-/// Save addon override configuration
+/// Force a refresh of the remote addon catalog
+#[tauri::command]
+async fn refresh_addon_catalog(
+    flavor: GameFlavor,
+    http: tauri::State<'_, SharedHttpClient>,
+) -> Result<Vec<AddonDefinition>, String> {
+    let config = load_config().unwrap_or_default();
+
+    catalog::refresh_catalog(&http.client(), config.catalog_url(), true).await?;
+
+    Ok(resolve_addon_list(&config, flavor))
+}
+
+/// Save addon override configuration, for one flavor or for all of them
 #[tauri::command]
 fn save_addon_override(
-    addon_name: String,
+    addon_name: AddonFolderName,
     update_mode: UpdateMode,
     branch: Option<String>,
+    flavor: Option<GameFlavor>,
 ) -> Result<(), String> {
-    let config_path = get_config_path();
+    update_config(|config| {
+        let overrides = match flavor {
+            Some(flavor) => config.flavor_overrides.entry(flavor).or_default(),
+            None => &mut config.addon_overrides,
+        };
 
-    // Load existing config
-    let mut config = load_config().unwrap_or(AppConfig {
-        wow_path: None,
-        launch_on_startup: true,
-        minimize_on_startup: true,
-        language: "en".to_string(),
-        addon_overrides: HashMap::new(),
-    });
-
-    // Update override for this addon
-    config.addon_overrides.insert(
-        addon_name,
-        AddonOverride {
-            update_mode: Some(update_mode),
-            branch,
-        },
-    );
-
-    // Save config
-    if let Some(parent) = config_path.parent() {
-        fs::create_dir_all(parent)
-            .map_err(|e| format!("Failed to create config directory: {}", e))?;
+        // Update override for this addon
+        overrides.insert(
+            addon_name.to_string(),
+            AddonOverride {
+                update_mode: Some(update_mode),
+                branch,
+            },
+        );
+        Ok(())
+    })
+}
+
+// ===========================
+// CUSTOM ADDONS
+// ===========================
+
+/// Check a GitHub user or organization name
+fn is_valid_github_owner(owner: &str) -> bool {
+    !owner.is_empty()
+        && owner.len() <= 39
+        && !owner.starts_with('-')
+        && !owner.ends_with('-')
+        && owner.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+}
+
+/// Check a GitHub repository name
+fn is_valid_github_repo(repo: &str) -> bool {
+    !repo.is_empty()
+        && repo.len() <= 100
+        && repo != "."
+        && repo != ".."
+        && repo.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.')
+}
+
+/// Validate a user-defined addon before it is stored in the config
+fn validate_custom_addon(addon: &AddonDefinition) -> Result<(), String> {
+    if !is_valid_github_owner(&addon.github_owner) {
+        return Err(format!("Invalid GitHub owner '{}'", addon.github_owner));
+    }
+    if !is_valid_github_repo(&addon.github_repo) {
+        return Err(format!("Invalid GitHub repository '{}'", addon.github_repo));
     }
 
-    let json = serde_json::to_string_pretty(&config)
-        .map_err(|e| format!("Failed to serialize config: {}", e))?;
+    if addon.update_mode == UpdateMode::Branch
+        && addon.branch.as_deref().is_none_or(|b| b.trim().is_empty())
+    {
+        return Err("Branch name is required for branch mode".to_string());
+    }
 
-    fs::write(&config_path, json)
-        .map_err(|e| format!("Failed to write config file: {}", e))?;
+    // Built-in catalog entries always win over custom ones
+    if catalog::load_addon_definitions()
+        .iter()
+        .any(|def| def.local_name.eq_ignore_ascii_case(&addon.local_name))
+    {
+        return Err(format!(
+            "Addon '{}' conflicts with a built-in catalog addon",
+            addon.local_name
+        ));
+    }
 
     Ok(())
 }
 
+/// Add a user-defined addon to the config
+#[tauri::command]
+fn add_custom_addon(addon: AddonDefinition) -> Result<(), String> {
+    validate_custom_addon(&addon)?;
+
+    update_config(|config| {
+        if config
+            .custom_addons
+            .iter()
+            .any(|def| def.local_name.eq_ignore_ascii_case(&addon.local_name))
+        {
+            return Err(format!("Custom addon '{}' already exists", addon.local_name));
+        }
+
+        config.custom_addons.push(addon);
+        Ok(())
+    })
+}
+
+/// Replace a user-defined addon, possibly renaming it
+#[tauri::command]
+fn update_custom_addon(local_name: AddonFolderName, addon: AddonDefinition) -> Result<(), String> {
+    validate_custom_addon(&addon)?;
+
+    update_config(|config| {
+        if config.custom_addons.iter().any(|def| {
+            def.local_name != local_name && def.local_name.eq_ignore_ascii_case(&addon.local_name)
+        }) {
+            return Err(format!("Custom addon '{}' already exists", addon.local_name));
+        }
+
+        let existing = config
+            .custom_addons
+            .iter_mut()
+            .find(|def| def.local_name == local_name)
+            .ok_or_else(|| format!("Custom addon '{}' not found", local_name))?;
+
+        *existing = addon;
+        Ok(())
+    })
+}
+
+/// Remove a user-defined addon from the config
+#[tauri::command]
+fn remove_custom_addon(local_name: AddonFolderName) -> Result<(), String> {
+    update_config(|config| {
+        let count = config.custom_addons.len();
+        config.custom_addons.retain(|def| def.local_name != local_name);
+
+        if config.custom_addons.len() == count {
+            return Err(format!("Custom addon '{}' not found", local_name));
+        }
+
+        config.addon_overrides.remove(local_name.as_str());
+        for overrides in config.flavor_overrides.values_mut() {
+            overrides.remove(local_name.as_str());
+        }
+        Ok(())
+    })
+}
+
 // ===========================
 // GITHUB API
 // ===========================
 
 /// Fetch latest release from GitHub
 #[tauri::command]
-async fn fetch_github_release(owner: String, repo: String) -> Result<GitHubRelease, String> {
-    let url = format!("https://api.github.com/repos/{}/{}/releases/latest", owner, repo);
+async fn fetch_github_release(
+    owner: String,
+    repo: String,
+    http: tauri::State<'_, SharedHttpClient>,
+) -> Result<GitHubRelease, String> {
+    github_release(&http.client(), &owner, &repo).await
+}
 
-    let client = reqwest::Client::builder()
-        .user_agent("Nihui-App/0.1.0")
-        .build()
-        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+/// Latest release of a repository
+async fn github_release(http: &HttpClient, owner: &str, repo: &str) -> Result<GitHubRelease, String> {
+    let url = http.api_url(&format!("/repos/{}/{}/releases/latest", owner, repo));
 
-    let response = client
-        .get(&url)
-        .send()
+    let response = http
+        .github_fetch(&url)
         .await
         .map_err(|e| format!("Failed to fetch release: {}", e))?;
 
-    if !response.status().is_success() {
-        return Err(format!("GitHub API error: {}", response.status()));
+    if !response.status.is_success() {
+        return Err(http.status_error(response.status, &format!("latest release of {}/{}", owner, repo)));
     }
 
-    let release: GitHubRelease = response
-        .json()
-        .await
+    let release: GitHubRelease = serde_json::from_str(&response.body)
         .map_err(|e| format!("Failed to parse release JSON: {}", e))?;
 
     Ok(release)
@@ -481,105 +668,118 @@ async fn fetch_github_release(owner: String, repo: String) -> Result<GitHubRelea
 
 /// Fetch latest commit from a branch
 #[tauri::command]
-async fn fetch_github_branch(owner: String, repo: String, branch: String) -> Result<String, String> {
-    let url = format!("https://api.github.com/repos/{}/{}/commits/{}", owner, repo, branch);
+async fn fetch_github_branch(
+    owner: String,
+    repo: String,
+    branch: String,
+    http: tauri::State<'_, SharedHttpClient>,
+) -> Result<String, String> {
+    let sha = fetch_commit_sha(&http.client(), &owner, &repo, &branch).await?;
 
-    let client = reqwest::Client::builder()
-        .user_agent("Nihui-App/0.1.0")
-        .build()
-        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    // Get the short SHA
+    Ok(sha.chars().take(7).collect())
+}
 
-    let response = client
-        .get(&url)
-        .send()
+/// Resolve a branch, tag or SHA to its full commit SHA
+async fn fetch_commit_sha(http: &HttpClient, owner: &str, repo: &str, reference: &str) -> Result<String, String> {
+    let url = http.api_url(&format!("/repos/{}/{}/commits/{}", owner, repo, reference));
+
+    let response = http
+        .github_fetch(&url)
         .await
         .map_err(|e| format!("Failed to fetch branch: {}", e))?;
 
-    if !response.status().is_success() {
-        return Err(format!("GitHub API error: {}", response.status()));
+    if !response.status.is_success() {
+        return Err(http.status_error(response.status, &format!("{} of {}/{}", reference, owner, repo)));
     }
 
-    let json: serde_json::Value = response
-        .json()
-        .await
+    let json: serde_json::Value = serde_json::from_str(&response.body)
         .map_err(|e| format!("Failed to parse commit JSON: {}", e))?;
 
-    // Get the short SHA
-    let sha = json["sha"]
+    json["sha"]
         .as_str()
-        .ok_or("Failed to get commit SHA")?
-        .chars()
-        .take(7)
-        .collect::<String>();
-
-    Ok(sha)
+        .map(|sha| sha.to_string())
+        .ok_or_else(|| "Failed to get commit SHA".to_string())
 }
 
 /// Fetch TOC file content from GitHub for version comparison
 #[tauri::command]
-async fn fetch_github_toc(owner: String, repo: String, branch: String, addon_name: String) -> Result<String, String> {
-    // Raw GitHub URL: https://raw.githubusercontent.com/{owner}/{repo}/{branch}/{addon_name}.toc
-    // The .toc file is at the root of the repo
-    let url = format!(
-        "https://raw.githubusercontent.com/{}/{}/{}/{}.toc",
-        owner, repo, branch, addon_name
-    );
-
-    let client = reqwest::Client::builder()
-        .user_agent("Nihui-App/0.1.0")
-        .build()
-        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
-
-    let response = client
-        .get(&url)
-        .send()
+async fn fetch_github_toc(
+    owner: String,
+    repo: String,
+    branch: String,
+    addon_name: AddonFolderName,
+    flavor: GameFlavor,
+    http: tauri::State<'_, SharedHttpClient>,
+) -> Result<String, String> {
+    github_toc_version(&http.client(), &owner, &repo, &branch, &addon_name, flavor).await
+}
+
+/// Version in the TOC file of an addon at the root of a repository branch
+async fn github_toc_version(
+    http: &HttpClient,
+    owner: &str,
+    repo: &str,
+    branch: &str,
+    addon_name: &str,
+    flavor: GameFlavor,
+) -> Result<String, String> {
+    // The .toc files are at the root of the repo: list it once and fetch only
+    // the file the game would load, instead of probing every candidate
+    let url = http.api_url(&format!("/repos/{}/{}/contents?ref={}", owner, repo, branch));
+    let response = http
+        .github_fetch(&url)
         .await
-        .map_err(|e| format!("Failed to fetch TOC: {}", e))?;
+        .map_err(|e| format!("Failed to list repository files: {}", e))?;
 
-    if !response.status().is_success() {
-        return Err(format!("GitHub error: {}", response.status()));
+    if !response.status.is_success() {
+        return Err(http.status_error(response.status, &format!("branch {} of {}/{}", branch, owner, repo)));
     }
 
-    let content = response
-        .text()
-        .await
-        .map_err(|e| format!("Failed to read TOC content: {}", e))?;
+    let entries: Vec<serde_json::Value> = serde_json::from_str(&response.body)
+        .map_err(|e| format!("Failed to parse repository contents JSON: {}", e))?;
+    let file_names: Vec<&str> = entries
+        .iter()
+        .filter(|entry| entry["type"] == "file")
+        .filter_map(|entry| entry["name"].as_str())
+        .collect();
+    let toc_name = toc::pick_toc(&file_names, addon_name, flavor.toc_client())
+        .ok_or_else(|| format!("No TOC file for {} found in {}/{}", addon_name, owner, repo))?;
 
-    // Extract version from TOC content
-    let re = Regex::new(r"(?m)^##\s*Version:\s*(.+)$").unwrap();
+    let response = http
+        .github_file(owner, repo, branch, toc_name)
+        .await
+        .map_err(|e| format!("Failed to fetch TOC: {}", e))?;
 
-    if let Some(cap) = re.captures(&content) {
-        if let Some(version) = cap.get(1) {
-            return Ok(version.as_str().trim().to_string());
-        }
+    if !response.status.is_success() {
+        return Err(http.status_error(response.status, &format!("{} in {}/{}", toc_name, owner, repo)));
     }
 
-    Err("Version not found in TOC file".to_string())
+    toc::TocFile::parse(&response.body)
+        .version
+        .ok_or_else(|| "Version not found in TOC file".to_string())
 }
 
 /// Fetch list of branches from GitHub repository
 #[tauri::command]
-async fn fetch_github_branches(owner: String, repo: String) -> Result<Vec<String>, String> {
-    let url = format!("https://api.github.com/repos/{}/{}/branches", owner, repo);
-
-    let client = reqwest::Client::builder()
-        .user_agent("Nihui-App/0.1.0")
-        .build()
-        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
-
-    let response = client
-        .get(&url)
-        .send()
+async fn fetch_github_branches(
+    owner: String,
+    repo: String,
+    http: tauri::State<'_, SharedHttpClient>,
+) -> Result<Vec<String>, String> {
+    let http = http.client();
+    let url = http.api_url(&format!("/repos/{}/{}/branches", owner, repo));
+
+    let response = http
+        .github_fetch(&url)
         .await
         .map_err(|e| format!("Failed to fetch branches: {}", e))?;
 
-    if !response.status().is_success() {
-        return Err(format!("GitHub API error: {}", response.status()));
+    if !response.status.is_success() {
+        return Err(http.status_error(response.status, &format!("repository {}/{}", owner, repo)));
     }
 
-    let branches: Vec<serde_json::Value> = response
-        .json()
-        .await
+    let branches: Vec<serde_json::Value> = serde_json::from_str(&response.body)
         .map_err(|e| format!("Failed to parse branches JSON: {}", e))?;
 
     let branch_names: Vec<String> = branches
@@ -592,34 +792,25 @@ async fn fetch_github_branches(owner: String, repo: String) -> Result<Vec<String
 
 /// Fetch README.md content from GitHub
 #[tauri::command]
-async fn fetch_github_readme(owner: String, repo: String, branch: String) -> Result<String, String> {
+async fn fetch_github_readme(
+    owner: String,
+    repo: String,
+    branch: String,
+    http: tauri::State<'_, SharedHttpClient>,
+) -> Result<String, String> {
     // Try README.md first, then README.MD, then readme.md
     let possible_names = vec!["README.md", "README.MD", "readme.md"];
 
-    let client = reqwest::Client::builder()
-        .user_agent("Nihui-App/0.1.0")
-        .build()
-        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    let http = http.client();
 
     for readme_name in possible_names {
-        let url = format!(
-            "https://raw.githubusercontent.com/{}/{}/{}/{}",
-            owner, repo, branch, readme_name
-        );
-
-        let response = client
-            .get(&url)
-            .send()
+        let response = http
+            .github_file(&owner, &repo, &branch, readme_name)
             .await
             .map_err(|e| format!("Failed to fetch README: {}", e))?;
 
-        if response.status().is_success() {
-            let content = response
-                .text()
-                .await
-                .map_err(|e| format!("Failed to read README content: {}", e))?;
-
-            return Ok(content);
+        if response.status.is_success() {
+            return Ok(response.body);
         }
     }
 
@@ -700,101 +891,117 @@ fn get_tray_icon_path(app: tauri::AppHandle, icon_name: String) -> Result<String
 /// Install or update an addon from GitHub
 #[tauri::command]
 async fn install_addon(
+    app: tauri::AppHandle,
     wow_path: String,
+    flavor: GameFlavor,
     addon_def: AddonDefinition,
+    allow_downgrade: Option<bool>,
 ) -> Result<String, String> {
-    // Build download URL based on update mode
-    let download_url = match addon_def.update_mode {
+    // Only addons from the catalog or the user's custom list can be installed
+    let config = load_config().unwrap_or_default();
+    let http = app.state::<SharedHttpClient>().client();
+    if !resolve_addon_list(&config, flavor)
+        .iter()
+        .any(|def| def.local_name == addon_def.local_name)
+    {
+        return Err(format!("Unknown addon '{}'", addon_def.local_name));
+    }
+
+    // Only one install per addon at a time, claimed before the first request
+    // so nothing can race it; the guard unregisters it on return
+    let registry = app.state::<download::InstallRegistry>();
+    let guard = registry.begin(flavor, &addon_def.local_name)?;
+
+    // Installing an older version than the one on disk needs explicit confirmation
+    if !allow_downgrade.unwrap_or(false) {
+        if let Ok(check) = update_check::check_update(&http, &wow_path, flavor, &addon_def).await {
+            if check.comparison == Some(version::VersionComparison::Older) {
+                return Err(format!(
+                    "Installing version {} would downgrade {} from version {}",
+                    check.remote_version.unwrap_or_default(),
+                    addon_def.local_name,
+                    check.local_version.unwrap_or_default()
+                ));
+            }
+        }
+    }
+
+    // Build download URL based on update mode, and resolve what it points to
+    let (download_url, commit_sha, tag) = match addon_def.update_mode {
         UpdateMode::Release => {
             // Get latest release to get zipball URL
-            let release = fetch_github_release(
-                addon_def.github_owner.clone(),
-                addon_def.github_repo.clone(),
+            let release = github_release(&http, &addon_def.github_owner, &addon_def.github_repo).await?;
+            let commit_sha = fetch_commit_sha(
+                &http,
+                &addon_def.github_owner,
+                &addon_def.github_repo,
+                &release.tag_name,
             )
-            .await?;
-            release.zipball_url
+            .await
+            .ok();
+            (release.zipball_url, commit_sha, Some(release.tag_name))
         }
         UpdateMode::Branch => {
             let branch = addon_def.branch.as_ref().ok_or("Branch name is required for branch mode")?;
-            format!(
-                "https://github.com/{}/{}/archive/refs/heads/{}.zip",
-                addon_def.github_owner, addon_def.github_repo, branch
+            // Pin the download to the current head so the record matches the files
+            let commit_sha = fetch_commit_sha(
+                &http,
+                &addon_def.github_owner,
+                &addon_def.github_repo,
+                branch,
             )
+            .await?;
+            let url = http.zipball_url(&addon_def.github_owner, &addon_def.github_repo, &commit_sha);
+            (url, Some(commit_sha), None)
         }
     };
 
-    // Download ZIP
-    let client = reqwest::Client::builder()
-        .user_agent("Nihui-App/0.1.0")
-        .build()
-        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
-
-    let response = client
-        .get(&download_url)
-        .send()
-        .await
-        .map_err(|e| format!("Failed to download addon: {}", e))?;
-
-    if !response.status().is_success() {
-        return Err(format!("Download failed: HTTP {}", response.status()));
-    }
-
-    let zip_bytes = response
-        .bytes()
-        .await
-        .map_err(|e| format!("Failed to read download data: {}", e))?;
+    let mut reporter = download::ProgressReporter::new(app.clone(), flavor, &addon_def.local_name);
 
-    // Create temp directory for extraction
+    // Temp directory for the download and the extraction, removed on drop
+    // (including when the install fails or is cancelled)
     let temp_dir = tempfile::tempdir()
         .map_err(|e| format!("Failed to create temp directory: {}", e))?;
+    let zip_path = temp_dir.path().join("download.zip");
+    let extract_path = temp_dir.path().join("extracted");
 
-    // Extract ZIP
-    let cursor = std::io::Cursor::new(zip_bytes);
-    let mut archive = ZipArchive::new(cursor)
-        .map_err(|e| format!("Failed to open ZIP archive: {}", e))?;
-
-    for i in 0..archive.len() {
-        let mut file = archive.by_index(i)
-            .map_err(|e| format!("Failed to read ZIP entry: {}", e))?;
-
-        let outpath = match file.enclosed_name() {
-            Some(path) => temp_dir.path().join(path),
-            None => continue,
-        };
-
-        if file.name().ends_with('/') {
-            fs::create_dir_all(&outpath)
-                .map_err(|e| format!("Failed to create directory: {}", e))?;
-        } else {
-            if let Some(p) = outpath.parent() {
-                if !p.exists() {
-                    fs::create_dir_all(p)
-                        .map_err(|e| format!("Failed to create parent directory: {}", e))?;
-                }
-            }
-            let mut outfile = fs::File::create(&outpath)
-                .map_err(|e| format!("Failed to create file: {}", e))?;
-            std::io::copy(&mut file, &mut outfile)
-                .map_err(|e| format!("Failed to extract file: {}", e))?;
-        }
-    }
+    // Download ZIP
+    download::download_to_file(
+        &http,
+        &download_url,
+        &zip_path,
+        &addon_def.local_name,
+        &mut reporter,
+        &guard.cancel,
+    )
+    .await?;
+
+    // Extract ZIP on the blocking pool, then take the reporter back
+    let mut reporter = {
+        let (zip_path, extract_path) = (zip_path.clone(), extract_path.clone());
+        let (local_name, cancel) = (addon_def.local_name.clone(), guard.cancel.clone());
+        tokio::task::spawn_blocking(move || {
+            download::extract_zip(&zip_path, &extract_path, &local_name, &mut reporter, &cancel)
+                .map(|()| reporter)
+        })
+        .await
+        .map_err(|e| format!("Extraction of {} failed: {}", addon_def.local_name, e))??
+    };
 
     // Find the addon folder in the extracted content
     // GitHub archives have structure: repo-name-branch/ (for branch mode) or repo-name-sha/ (for release mode)
     // The addon files are directly in this root folder
     let addon_folder_name = &addon_def.local_name;
-    let toc_file_name = format!("{}.toc", addon_folder_name);
 
-    // Find the root folder that contains the .toc file
+    // Find the root folder that contains a .toc file for the addon
     let mut addon_source_path: Option<PathBuf> = None;
 
-    if let Ok(entries) = fs::read_dir(temp_dir.path()) {
+    if let Ok(entries) = fs::read_dir(&extract_path) {
         for entry in entries.flatten() {
             if let Ok(file_type) = entry.file_type() {
                 if file_type.is_dir() {
                     // Check if this directory contains the .toc file
-                    let toc_path = entry.path().join(&toc_file_name);
-                    if toc_path.exists() {
+                    if toc::find_toc(&entry.path(), addon_folder_name, flavor.toc_client()).is_some() {
                         addon_source_path = Some(entry.path());
                         break;
                     }
@@ -804,25 +1011,43 @@ async fn install_addon(
     }
 
     let source_path = addon_source_path.ok_or_else(|| {
-        format!("Addon files with '{}' not found in the downloaded archive", toc_file_name)
+        format!("Addon files with '{}.toc' not found in the downloaded archive", addon_folder_name)
     })?;
 
-    // Destination path in WoW AddOns directory
-    let addons_path = PathBuf::from(&wow_path)
-        .join("_retail_")
-        .join("Interface")
-        .join("AddOns");
+    // Destination path in the flavor's AddOns directory
+    let addons_path = flavor.addons_path(&wow_path);
 
     let dest_path = addons_path.join(addon_folder_name);
 
-    // Remove existing addon if present
-    if dest_path.exists() {
-        fs::remove_dir_all(&dest_path)
-            .map_err(|e| format!("Failed to remove existing addon: {}", e))?;
+    // Last chance to cancel before touching the AddOns folder
+    guard.cancel.check(&addon_def.local_name)?;
+
+    // Keep the installed version so it can be rolled back to later
+    if config.version_retention > 0 {
+        history::archive_installed(flavor, &dest_path, addon_folder_name)?;
+        history::prune(flavor, addon_folder_name, config.version_retention);
     }
 
-    // Copy addon to WoW AddOns directory
-    copy_dir_all(&source_path, &dest_path)?;
+    // Stage the new version next to AddOns and swap it in; the previous
+    // version is restored if anything fails along the way
+    let file_count = download::count_files(&source_path);
+    reporter.report(download::InstallPhase::Copy, 0, Some(file_count));
+    swap::replace_addon_dir(&source_path, &addons_path, flavor, addon_folder_name)?;
+    reporter.report(download::InstallPhase::Copy, file_count, Some(file_count));
+
+    // Record exactly what was installed
+    let record = install_record::InstallRecord {
+        local_name: addon_folder_name.clone(),
+        flavor,
+        source_url: download_url,
+        commit_sha,
+        tag,
+        update_mode: addon_def.update_mode.clone(),
+        branch: addon_def.branch.clone(),
+        installed_at: install_record::now_timestamp(),
+        files: install_record::hash_addon_files(&dest_path)?,
+    };
+    install_record::save_record(&record)?;
 
     Ok(format!("Successfully installed {} to {}", addon_folder_name, dest_path.display()))
 }
@@ -852,29 +1077,66 @@ fn copy_dir_all(src: &Path, dst: &Path) -> Result<(), String> {
 // ADDON UNINSTALLATION
 // ===========================
 
-/// Uninstall an addon by removing its directory
+/// What an uninstall removed
+#[derive(Debug, Serialize, Clone)]
+struct UninstallReport {
+    local_name: AddonFolderName,
+    quarantine_id: String, // Pass to `restore_addon` to undo
+    saved_variables: Vec<saved_variables::SavedVariablesFile>, // Empty unless requested
+}
+
+/// Uninstall an addon by moving its directory to the quarantine,
+/// optionally with its SavedVariables files
 #[tauri::command]
-fn uninstall_addon(
+async fn uninstall_addon(
+    app: tauri::AppHandle,
     wow_path: String,
-    addon_name: String,
-) -> Result<String, String> {
+    flavor: GameFlavor,
+    addon_name: AddonFolderName,
+    remove_saved_variables: Option<bool>,
+) -> Result<UninstallReport, String> {
+    // Only folders the app knows about may be removed
+    let config = load_config()?;
+    let is_known = resolve_addon_list(&config, flavor)
+        .iter()
+        .any(|def| def.local_name == addon_name)
+        || install_record::load_record(flavor, &addon_name).is_some();
+    if !is_known {
+        return Err(format!("Addon '{}' is not managed by this app", addon_name));
+    }
+
+    // Not in the middle of an install or rollback of the same addon
+    let _guard = app.state::<download::InstallRegistry>().begin(flavor, &addon_name)?;
+
     // Build path to addon directory
-    let addon_path = PathBuf::from(&wow_path)
-        .join("_retail_")
-        .join("Interface")
-        .join("AddOns")
-        .join(&addon_name);
+    let addon_path = flavor.addons_path(&wow_path).join(&addon_name);
 
     // Check if addon exists
     if !addon_path.exists() {
         return Err(format!("Addon '{}' is not installed", addon_name));
     }
 
-    // Remove addon directory
-    fs::remove_dir_all(&addon_path)
-        .map_err(|e| format!("Failed to uninstall addon: {}", e))?;
-
-    Ok(format!("Successfully uninstalled {}", addon_name))
+    // Keep the folder in quarantine so the uninstall can be undone
+    let wtf_path = flavor.wtf_path(&wow_path);
+    let local_name = addon_name.clone();
+    let entry = tokio::task::spawn_blocking(move || {
+        let entry = quarantine::quarantine_addon(
+            flavor,
+            &addon_path,
+            &local_name,
+            remove_saved_variables.unwrap_or(false).then_some(wtf_path.as_path()),
+        )?;
+        install_record::remove_record(flavor, &local_name)?;
+        Ok::<_, String>(entry)
+    })
+    .await
+    .map_err(|e| format!("Uninstall of {} failed: {}", addon_name, e))??;
+
+    Ok(UninstallReport {
+        local_name: addon_name,
+        quarantine_id: entry.id,
+        saved_variables: entry.saved_variables,
+    })
 }
 
 // ===========================
@@ -889,26 +1151,69 @@ fn main() {
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_notification::init())
         .plugin(tauri_plugin_autostart::init(tauri_plugin_autostart::MacosLauncher::LaunchAgent, Some(vec!["--minimized"])))
+        .manage(download::InstallRegistry::default())
         .invoke_handler(tauri::generate_handler![
             find_wow_path,
             validate_wow_path,
             get_local_addon_versions,
             get_addon_list,
+            refresh_addon_catalog,
             save_config,
             load_config,
             save_addon_override,
+            add_custom_addon,
+            update_custom_addon,
+            remove_custom_addon,
             fetch_github_release,
             fetch_github_branch,
             fetch_github_branches,
             fetch_github_toc,
             fetch_github_readme,
+            build_info::get_build_info,
+            http::get_rate_limit,
+            http::set_github_token,
+            http::has_github_token,
+            oauth::start_github_login,
+            oauth::complete_github_login,
+            oauth::cancel_github_login,
+            oauth::logout_github,
+            oauth::get_github_account,
+            update_check::check_addon_update,
+            update_check::check_all_updates,
+            scheduler::trigger_update_check,
+            scheduler::get_scheduler_state,
             get_tray_icon_path,
             install_addon,
             uninstall_addon,
+            download::cancel_install,
+            history::list_addon_versions,
+            history::rollback_addon,
+            quarantine::list_quarantined,
+            quarantine::restore_addon,
+            saved_variables::list_saved_variables,
+            install_record::get_install_record,
+            install_record::get_install_records,
+            install_record::get_changed_files,
             quit_app,
             get_app_version,
         ])
         .setup(|app| {
+            // One HTTP client for every request, rebuilt when the config or the token changes
+            let secrets = secrets::default_store();
+            let _ = secrets::migrate_config_token(secrets.as_ref());
+            app.manage(SharedHttpClient::new(&load_config().unwrap_or_default(), secrets)?);
+            app.manage(oauth::GitHubLogin::default());
+
+            // Finish or undo addon replacements interrupted by a crash
+            swap::recover_interrupted_swaps();
+
+            // Data written before flavors existed belongs to retail
+            install_record::migrate_legacy_records();
+            history::migrate_legacy_versions();
+
+            // Drop uninstalled addons kept longer than configured
+            quarantine::purge_expired(load_config().unwrap_or_default().quarantine_days);
+
             // Check if app was launched with --minimized flag
             let args: Vec<String> = std::env::args().collect();
             let launched_minimized = args.iter().any(|arg| arg == "--minimized");
@@ -925,6 +1230,9 @@ fn main() {
                 }
             }
 
+            // Background update checks run in the core, independent of the webview
+            scheduler::start(app.handle().clone());
+
             Ok(())
         })
         .on_window_event(|window, event| {
@@ -940,3 +1248,64 @@ fn main() {
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stub_server::{StubResponse, StubServer};
+
+    #[tokio::test]
+    async fn reads_the_remote_toc_with_one_listing_and_one_file_request() {
+        let server = StubServer::start(vec![
+            StubResponse::json(serde_json::json!([
+                { "name": "Libs", "type": "dir" },
+                { "name": "Nihui_uf.toc", "type": "file" },
+                { "name": "Nihui_uf_Vanilla.toc", "type": "file" },
+            ])),
+            StubResponse::text("## Interface: 11507\n## Version: 2.1.0\n"),
+        ])
+        .await;
+        let cache_dir = tempfile::tempdir().unwrap();
+        let config = AppConfig {
+            github_base_url: Some(server.url.clone()),
+            ..AppConfig::default()
+        };
+        let http = HttpClient::new(&config, None).unwrap().with_cache_dir(cache_dir.path());
+
+        let version = github_toc_version(&http, "nihui", "Nihui_uf", "main", "Nihui_uf", GameFlavor::ClassicEra).await;
+
+        assert_eq!(version.unwrap(), "2.1.0");
+        let requests = server.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].path, "/repos/nihui/Nihui_uf/contents?ref=main");
+        assert_eq!(requests[1].path, "/nihui/Nihui_uf/main/Nihui_uf_Vanilla.toc");
+    }
+
+    #[test]
+    fn saving_keeps_the_fields_written_by_backend_commands() {
+        let mut current = AppConfig::default();
+        current.custom_addons.push(AddonDefinition {
+            local_name: AddonFolderName::from_static("Custom_addon"),
+            nice_name: "Custom addon".to_string(),
+            github_owner: "someone".to_string(),
+            github_repo: "Custom_addon".to_string(),
+            description: String::new(),
+            update_mode: UpdateMode::Release,
+            branch: None,
+        });
+        current.flavor_overrides.entry(GameFlavor::Classic).or_default().insert(
+            "Nihui_uf".to_string(),
+            AddonOverride { update_mode: Some(UpdateMode::Branch), branch: Some("dev".to_string()) },
+        );
+        current.last_seen_builds.insert(GameFlavor::Retail, 57_000);
+
+        // Loaded by the UI before the backend changes above
+        let mut from_ui = AppConfig { language: "fr".to_string(), ..AppConfig::default() };
+        from_ui.keep_backend_fields(&mut current);
+
+        assert_eq!(from_ui.language, "fr");
+        assert_eq!(from_ui.custom_addons.len(), 1);
+        assert!(from_ui.flavor_overrides[&GameFlavor::Classic].contains_key("Nihui_uf"));
+        assert_eq!(from_ui.last_seen_builds.get(&GameFlavor::Retail), Some(&57_000));
+    }
+}
@@ -0,0 +1,405 @@
+use reqwest::header::ACCEPT;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::http::{HttpClient, SharedHttpClient};
+use crate::{load_config, AppConfig};
+
+// ===========================
+// GITHUB DEVICE LOGIN
+// ===========================
+
+/// Client ID of the GitHub OAuth app, set when building releases
+const BUILT_IN_CLIENT_ID: Option<&str> = option_env!("NIHUI_GITHUB_CLIENT_ID");
+
+/// Read access to private repositories needs the full repo scope
+const OAUTH_SCOPE: &str = "repo";
+
+const DEVICE_GRANT_TYPE: &str = "urn:ietf:params:oauth:grant-type:device_code";
+
+/// Added to the polling interval each time GitHub answers slow_down
+const SLOW_DOWN_STEP: Duration = Duration::from_secs(5);
+
+fn client_id(config: &AppConfig) -> Result<String, String> {
+    config
+        .github_client_id
+        .as_deref()
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .or(BUILT_IN_CLIENT_ID)
+        .map(str::to_string)
+        .ok_or_else(|| {
+            "No GitHub OAuth client ID: build with NIHUI_GITHUB_CLIENT_ID set or add github_client_id to the config"
+                .to_string()
+        })
+}
+
+/// Answer of GitHub to a device code request
+#[derive(Debug, Deserialize)]
+struct DeviceCodeResponse {
+    device_code: String,
+    user_code: String,
+    verification_uri: String,
+    expires_in: u64,
+    interval: u64,
+}
+
+/// What the user needs to approve the login on GitHub
+#[derive(Debug, Serialize, Clone)]
+pub struct DeviceLogin {
+    pub user_code: String,
+    pub verification_uri: String,
+    pub expires_in: u64, // Seconds the code stays valid
+}
+
+/// Outcome of one poll of the token endpoint
+#[derive(Debug, PartialEq)]
+pub enum TokenPoll {
+    Token(String),
+    Pending,
+    SlowDown(Option<u64>), // New minimum interval in seconds, when GitHub gives one
+    Failed(String),
+}
+
+/// Read an answer of the token endpoint, which reports its errors with 200 OK
+pub fn parse_token_response(response: &Value) -> TokenPoll {
+    if let Some(token) = response["access_token"].as_str().filter(|token| !token.is_empty()) {
+        return TokenPoll::Token(token.to_string());
+    }
+
+    match response["error"].as_str() {
+        Some("authorization_pending") => TokenPoll::Pending,
+        Some("slow_down") => TokenPoll::SlowDown(response["interval"].as_u64()),
+        Some("expired_token") => TokenPoll::Failed("The login code expired, please sign in again".to_string()),
+        Some("access_denied") => TokenPoll::Failed("The login was cancelled on GitHub".to_string()),
+        Some(error) => TokenPoll::Failed(
+            response["error_description"]
+                .as_str()
+                .map(str::to_string)
+                .unwrap_or_else(|| format!("GitHub login failed: {}", error)),
+        ),
+        None => TokenPoll::Failed("No token in GitHub login response".to_string()),
+    }
+}
+
+#[derive(Clone)]
+struct PendingLogin {
+    client_id: String,
+    device_code: String,
+    interval: Duration,
+    expires_at: Instant,
+}
+
+/// The login waiting for approval, if any
+#[derive(Default)]
+pub struct GitHubLogin(Mutex<Option<PendingLogin>>);
+
+impl GitHubLogin {
+    fn pending(&self) -> Option<PendingLogin> {
+        self.0.lock().ok().and_then(|pending| pending.clone())
+    }
+
+    fn set(&self, login: Option<PendingLogin>) {
+        if let Ok(mut pending) = self.0.lock() {
+            *pending = login;
+        }
+    }
+
+    /// Clear the login if it is still the one with this device code
+    fn finish(&self, device_code: &str) {
+        if let Ok(mut pending) = self.0.lock() {
+            if pending.as_ref().is_some_and(|login| login.device_code == device_code) {
+                *pending = None;
+            }
+        }
+    }
+}
+
+/// The signed-in GitHub account
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GitHubAccount {
+    pub login: String,
+    pub name: Option<String>,
+    pub avatar_url: String,
+    pub html_url: String,
+}
+
+async fn post_json(http: &HttpClient, path: &str, form: &[(&str, &str)]) -> Result<Value, String> {
+    let response = http
+        .post(&http.oauth_url(path))
+        .header(ACCEPT, "application/json")
+        .form(form)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach GitHub login: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(format!("GitHub login error: {}", response.status()));
+    }
+
+    response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse GitHub login response: {}", e))
+}
+
+async fn fetch_account(http: &HttpClient) -> Result<GitHubAccount, String> {
+    let response = http
+        .github_get(&http.api_url("/user"))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch GitHub account: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(http.status_error(response.status(), "the GitHub account"));
+    }
+
+    response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse GitHub account: {}", e))
+}
+
+/// Ask GitHub for a device code. The user enters the returned code at the
+/// verification URL, then `complete_github_login` waits for the approval.
+#[tauri::command]
+pub async fn start_github_login(
+    http: tauri::State<'_, SharedHttpClient>,
+    login: tauri::State<'_, GitHubLogin>,
+) -> Result<DeviceLogin, String> {
+    let client_id = client_id(&load_config().unwrap_or_default())?;
+
+    let response = post_json(
+        &http.client(),
+        "/login/device/code",
+        &[("client_id", client_id.as_str()), ("scope", OAUTH_SCOPE)],
+    )
+    .await?;
+
+    let device: DeviceCodeResponse = serde_json::from_value(response.clone()).map_err(|_| {
+        response["error_description"]
+            .as_str()
+            .map(str::to_string)
+            .unwrap_or_else(|| "Invalid device code response from GitHub".to_string())
+    })?;
+
+    login.set(Some(PendingLogin {
+        client_id,
+        device_code: device.device_code,
+        interval: Duration::from_secs(device.interval.max(1)),
+        expires_at: Instant::now() + Duration::from_secs(device.expires_in),
+    }));
+
+    Ok(DeviceLogin {
+        user_code: device.user_code,
+        verification_uri: device.verification_uri,
+        expires_in: device.expires_in,
+    })
+}
+
+/// Poll the token endpoint until the pending login is approved, then return
+/// the token. Fails when the login is denied, expires or is cancelled.
+async fn poll_for_token(http: &HttpClient, login: &GitHubLogin, slow_down_step: Duration) -> Result<String, String> {
+    let mut pending = login.pending().ok_or("No GitHub login in progress")?;
+
+    loop {
+        tokio::time::sleep(pending.interval).await;
+
+        // Cancelled, or replaced by a newer login, while waiting
+        if login.pending().map(|current| current.device_code) != Some(pending.device_code.clone()) {
+            return Err("GitHub login cancelled".to_string());
+        }
+        if Instant::now() >= pending.expires_at {
+            login.finish(&pending.device_code);
+            return Err("The login code expired, please sign in again".to_string());
+        }
+
+        let response = post_json(
+            http,
+            "/login/oauth/access_token",
+            &[
+                ("client_id", pending.client_id.as_str()),
+                ("device_code", pending.device_code.as_str()),
+                ("grant_type", DEVICE_GRANT_TYPE),
+            ],
+        )
+        .await;
+
+        let response = match response {
+            Ok(response) => response,
+            Err(e) => {
+                login.finish(&pending.device_code);
+                return Err(e);
+            }
+        };
+
+        match parse_token_response(&response) {
+            TokenPoll::Token(token) => {
+                login.finish(&pending.device_code);
+                return Ok(token);
+            }
+            TokenPoll::Pending => {}
+            TokenPoll::SlowDown(interval) => {
+                let slower = pending.interval + slow_down_step;
+                pending.interval = interval.map(Duration::from_secs).unwrap_or(slower).max(slower);
+            }
+            TokenPoll::Failed(message) => {
+                login.finish(&pending.device_code);
+                return Err(message);
+            }
+        }
+    }
+}
+
+/// Wait until the pending login is approved on GitHub, then store the token
+/// and return the account
+#[tauri::command]
+pub async fn complete_github_login(
+    http: tauri::State<'_, SharedHttpClient>,
+    login: tauri::State<'_, GitHubLogin>,
+) -> Result<GitHubAccount, String> {
+    let token = poll_for_token(&http.client(), &login, SLOW_DOWN_STEP).await?;
+
+    http.set_token(Some(&token))?;
+    fetch_account(&http.client()).await
+}
+
+/// Stop waiting for the pending login
+#[tauri::command]
+pub fn cancel_github_login(login: tauri::State<'_, GitHubLogin>) {
+    login.set(None);
+}
+
+/// Forget the GitHub token and the responses cached with it. The token stays
+/// authorized on GitHub until revoked in the account's application settings.
+#[tauri::command]
+pub fn logout_github(http: tauri::State<'_, SharedHttpClient>) -> Result<(), String> {
+    http.set_token(None)
+}
+
+/// The account the saved token belongs to, None when signed out
+#[tauri::command]
+pub async fn get_github_account(http: tauri::State<'_, SharedHttpClient>) -> Result<Option<GitHubAccount>, String> {
+    let http = http.client();
+    if !http.has_token() {
+        return Ok(None);
+    }
+    fetch_account(&http).await.map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stub_server::{StubResponse, StubServer};
+    use serde_json::json;
+
+    const STEP: Duration = Duration::from_millis(150);
+
+    fn client_for(server: &StubServer) -> HttpClient {
+        let config = AppConfig {
+            github_oauth_url: Some(server.url.clone()),
+            ..AppConfig::default()
+        };
+        HttpClient::new(&config, None).unwrap()
+    }
+
+    fn pending_login(expires_in: Duration) -> GitHubLogin {
+        let login = GitHubLogin::default();
+        login.set(Some(PendingLogin {
+            client_id: "Iv1.stub".to_string(),
+            device_code: "device123".to_string(),
+            interval: Duration::from_millis(20),
+            expires_at: Instant::now() + expires_in,
+        }));
+        login
+    }
+
+    #[test]
+    fn reads_every_token_endpoint_answer() {
+        assert_eq!(
+            parse_token_response(&json!({ "access_token": "gho_abc", "token_type": "bearer", "scope": "repo" })),
+            TokenPoll::Token("gho_abc".to_string())
+        );
+        assert_eq!(parse_token_response(&json!({ "error": "authorization_pending" })), TokenPoll::Pending);
+        assert_eq!(
+            parse_token_response(&json!({ "error": "slow_down", "interval": 10 })),
+            TokenPoll::SlowDown(Some(10))
+        );
+        assert!(matches!(parse_token_response(&json!({ "error": "expired_token" })), TokenPoll::Failed(_)));
+        assert_eq!(
+            parse_token_response(&json!({ "error": "incorrect_client_credentials", "error_description": "The client_id is not valid." })),
+            TokenPoll::Failed("The client_id is not valid.".to_string())
+        );
+    }
+
+    #[test]
+    fn configured_client_id_wins() {
+        let config = AppConfig {
+            github_client_id: Some(" Iv1.stub ".to_string()),
+            ..AppConfig::default()
+        };
+        assert_eq!(client_id(&config), Ok("Iv1.stub".to_string()));
+    }
+
+    #[tokio::test]
+    async fn polls_until_approved_and_slows_down_when_asked() {
+        let server = StubServer::start(vec![
+            StubResponse::json(json!({ "error": "authorization_pending" })),
+            StubResponse::json(json!({ "error": "slow_down" })),
+            StubResponse::json(json!({ "access_token": "gho_abc", "token_type": "bearer" })),
+        ])
+        .await;
+        let login = pending_login(Duration::from_secs(30));
+
+        let token = poll_for_token(&client_for(&server), &login, STEP).await;
+
+        assert_eq!(token, Ok("gho_abc".to_string()));
+        assert!(login.pending().is_none());
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 3);
+        assert!(requests.iter().all(|request| request.path == "/login/oauth/access_token"));
+        assert!(requests[0].body.contains("device_code=device123"));
+        // After slow_down the interval grows by the step
+        assert!(requests[2].at - requests[1].at >= Duration::from_millis(20) + STEP);
+    }
+
+    #[tokio::test]
+    async fn stops_when_denied_or_expired() {
+        let server = StubServer::start(vec![StubResponse::json(json!({ "error": "access_denied" }))]).await;
+        let login = pending_login(Duration::from_secs(30));
+        let denied = poll_for_token(&client_for(&server), &login, STEP).await;
+        assert_eq!(denied, Err("The login was cancelled on GitHub".to_string()));
+        assert!(login.pending().is_none());
+
+        let server = StubServer::start(vec![StubResponse::json(json!({ "error": "expired_token" }))]).await;
+        let login = pending_login(Duration::from_secs(30));
+        let expired = poll_for_token(&client_for(&server), &login, STEP).await;
+        assert_eq!(expired, Err("The login code expired, please sign in again".to_string()));
+
+        // Expired locally: no request at all
+        let server = StubServer::start(Vec::new()).await;
+        let login = pending_login(Duration::ZERO);
+        let expired = poll_for_token(&client_for(&server), &login, STEP).await;
+        assert_eq!(expired, Err("The login code expired, please sign in again".to_string()));
+        assert!(server.requests().is_empty());
+    }
+
+    #[tokio::test]
+    async fn stops_when_cancelled() {
+        let server = StubServer::start(Vec::new()).await;
+        let login = pending_login(Duration::from_secs(30));
+        let http = client_for(&server);
+
+        // Cancelled while waiting for the first poll
+        let (result, _) = tokio::join!(poll_for_token(&http, &login, STEP), async {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+            login.set(None);
+        });
+
+        assert_eq!(result, Err("GitHub login cancelled".to_string()));
+        assert!(server.requests().is_empty());
+    }
+}
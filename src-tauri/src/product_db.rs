@@ -0,0 +1,191 @@
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+// ===========================
+// BATTLE.NET PRODUCT DATABASE
+// ===========================
+
+/// One product installed through the Battle.net app
+#[derive(Debug, Serialize, Clone, PartialEq, Default)]
+pub struct ProductInstall {
+    pub uid: String,
+    pub product_code: String, // e.g. "wow", "wow_classic", "wowt"
+    pub install_path: String, // As written by the agent, usually with '/' separators
+    pub region: String,
+    pub branch: String,
+}
+
+impl ProductInstall {
+    /// Whether the product is one of the World of Warcraft clients
+    pub fn is_wow(&self) -> bool {
+        self.product_code.starts_with("wow")
+    }
+}
+
+// product.db is a protobuf message. Only the fields we use are decoded:
+//
+//   Database        { repeated ProductInstall product_installs = 1; ... }
+//   ProductInstall  { string uid = 1; string product_code = 2; UserSettings settings = 3; ... }
+//   UserSettings    { string install_path = 1; string play_region = 2; ... string branch = 10; ... }
+
+/// Value of a decoded protobuf field, only length-delimited ones are kept
+enum Field<'a> {
+    Varint,
+    Bytes(&'a [u8]),
+    Fixed,
+}
+
+/// Minimal protobuf wire format reader
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Reader { bytes, pos: 0 }
+    }
+
+    fn varint(&mut self) -> Result<u64, String> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = *self.bytes.get(self.pos).ok_or("Truncated varint")?;
+            self.pos += 1;
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err("Varint is too long".to_string())
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], String> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or("Truncated field")?;
+        let slice = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    /// Next field of the message, or None at its end
+    fn next_field(&mut self) -> Result<Option<(u64, Field<'a>)>, String> {
+        if self.pos >= self.bytes.len() {
+            return Ok(None);
+        }
+
+        let key = self.varint()?;
+        let field = match key & 0x7 {
+            0 => {
+                self.varint()?;
+                Field::Varint
+            }
+            1 => {
+                self.take(8)?;
+                Field::Fixed
+            }
+            2 => {
+                let len = usize::try_from(self.varint()?).map_err(|_| "Field is too long")?;
+                Field::Bytes(self.take(len)?)
+            }
+            5 => {
+                self.take(4)?;
+                Field::Fixed
+            }
+            wire_type => return Err(format!("Unsupported wire type {}", wire_type)),
+        };
+
+        Ok(Some((key >> 3, field)))
+    }
+}
+
+fn string_field(bytes: &[u8]) -> Result<String, String> {
+    String::from_utf8(bytes.to_vec()).map_err(|_| "Invalid UTF-8 in string field".to_string())
+}
+
+fn parse_settings(bytes: &[u8], install: &mut ProductInstall) -> Result<(), String> {
+    let mut reader = Reader::new(bytes);
+    while let Some((number, field)) = reader.next_field()? {
+        match (number, field) {
+            (1, Field::Bytes(value)) => install.install_path = string_field(value)?,
+            (2, Field::Bytes(value)) => install.region = string_field(value)?,
+            (10, Field::Bytes(value)) => install.branch = string_field(value)?,
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn parse_install(bytes: &[u8]) -> Result<ProductInstall, String> {
+    let mut install = ProductInstall::default();
+    let mut reader = Reader::new(bytes);
+    while let Some((number, field)) = reader.next_field()? {
+        match (number, field) {
+            (1, Field::Bytes(value)) => install.uid = string_field(value)?,
+            (2, Field::Bytes(value)) => install.product_code = string_field(value)?,
+            (3, Field::Bytes(value)) => parse_settings(value, &mut install)?,
+            _ => {}
+        }
+    }
+    Ok(install)
+}
+
+/// Decode every product install entry of a product.db file
+pub fn parse_product_db(bytes: &[u8]) -> Result<Vec<ProductInstall>, String> {
+    let mut installs = Vec::new();
+    let mut reader = Reader::new(bytes);
+    while let Some((number, field)) = reader.next_field()? {
+        if let (1, Field::Bytes(value)) = (number, field) {
+            installs.push(parse_install(value)?);
+        }
+    }
+    Ok(installs)
+}
+
+pub fn read_product_db(path: &Path) -> Result<Vec<ProductInstall>, String> {
+    let bytes = fs::read(path).map_err(|e| format!("Failed to read product.db: {}", e))?;
+    parse_product_db(&bytes).map_err(|e| format!("Failed to decode product.db: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(name: &str) -> Vec<u8> {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/product_db").join(name);
+        fs::read(path).unwrap()
+    }
+
+    #[test]
+    fn decodes_every_install() {
+        let installs = parse_product_db(&fixture("multi_install.db")).unwrap();
+        let codes: Vec<&str> = installs.iter().map(|i| i.product_code.as_str()).collect();
+
+        assert_eq!(codes, vec!["agent", "wow", "wow_classic_era", "wowt", "wow_classic"]);
+
+        let retail = &installs[1];
+        assert_eq!(retail.uid, "wow");
+        assert_eq!(retail.install_path, "D:/Jeux/World of Warcraft");
+        assert_eq!(retail.region, "eu");
+        assert_eq!(retail.branch, "eu");
+
+        // Renamed folders are found too, no matter the name
+        assert_eq!(installs[3].install_path, "E:/WoW Test Realm");
+        assert_eq!(installs[4].region, "us");
+        assert!(!installs[0].is_wow());
+        assert!(installs.iter().skip(1).all(ProductInstall::is_wow));
+    }
+
+    #[test]
+    fn decodes_empty_database() {
+        assert!(parse_product_db(&fixture("empty.db")).unwrap().is_empty());
+    }
+
+    #[test]
+    fn rejects_truncated_database() {
+        assert!(parse_product_db(&fixture("truncated.db")).is_err());
+    }
+}
@@ -0,0 +1,384 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::Manager;
+
+use crate::addon_name::AddonFolderName;
+use crate::flavor::GameFlavor;
+use crate::install_record::{self, InstallRecord};
+use crate::saved_variables::{self, SavedVariablesFile};
+use crate::{copy_dir_all, download, get_app_data_dir, read_addon_version};
+
+// ===========================
+// UNINSTALL QUARANTINE
+// ===========================
+
+/// Default number of days an uninstalled addon is kept before being purged
+pub const DEFAULT_QUARANTINE_DAYS: u64 = 7;
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// An uninstalled addon waiting in quarantine
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct QuarantinedAddon {
+    pub id: String,
+    pub local_name: AddonFolderName,
+    #[serde(default)]
+    pub flavor: GameFlavor,
+    pub version: Option<String>, // TOC version of the quarantined files
+    pub quarantined_at: u64, // Unix timestamp (seconds)
+    pub record: Option<InstallRecord>, // None if installed outside the app
+    #[serde(default)]
+    pub saved_variables: Vec<SavedVariablesFile>, // Removed along with the addon
+}
+
+fn get_quarantine_dir() -> PathBuf {
+    get_app_data_dir().join("quarantine")
+}
+
+fn get_entry_meta_path(dir: &Path) -> PathBuf {
+    dir.join("entry.json")
+}
+
+fn get_entry_files_path(dir: &Path) -> PathBuf {
+    dir.join("files")
+}
+
+fn get_entry_saved_variables_path(dir: &Path) -> PathBuf {
+    dir.join("saved_variables")
+}
+
+/// Calendar date (YYYY-MM-DD, UTC) of a Unix timestamp
+fn format_date(timestamp: u64) -> String {
+    // Civil-from-days conversion (Howard Hinnant's algorithm)
+    let days = (timestamp / SECONDS_PER_DAY) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+/// How far a failed folder move got
+#[derive(Debug)]
+enum MoveError {
+    NotMoved(String), // Source untouched, nothing left at the destination
+    SourceLeft(String), // Fully copied, but the source could only be partly removed
+}
+
+type MoveFn = fn(&Path, &Path) -> Result<(), MoveError>;
+
+/// Move a folder, falling back to copy + delete when the app data dir
+/// is on another volume than the game
+fn move_dir(from: &Path, to: &Path) -> Result<(), MoveError> {
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| MoveError::NotMoved(format!("Failed to create directory: {}", e)))?;
+    }
+
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+
+    if let Err(e) = copy_dir_all(from, to) {
+        let _ = fs::remove_dir_all(to);
+        return Err(MoveError::NotMoved(e));
+    }
+    fs::remove_dir_all(from)
+        .map_err(|e| MoveError::SourceLeft(format!("Failed to remove {}: {}", from.display(), e)))
+}
+
+/// Every addon in quarantine, newest first
+pub fn list_entries() -> Vec<QuarantinedAddon> {
+    list_entries_in(&get_quarantine_dir())
+}
+
+fn list_entries_in(quarantine_dir: &Path) -> Vec<QuarantinedAddon> {
+    let mut entries = Vec::new();
+
+    if let Ok(dirs) = fs::read_dir(quarantine_dir) {
+        for dir in dirs.flatten() {
+            let meta_path = get_entry_meta_path(&dir.path());
+            if let Ok(content) = fs::read_to_string(&meta_path) {
+                if let Ok(entry) = serde_json::from_str::<QuarantinedAddon>(&content) {
+                    entries.push(entry);
+                }
+            }
+        }
+    }
+
+    entries.sort_by(|a, b| b.quarantined_at.cmp(&a.quarantined_at).then_with(|| b.id.cmp(&a.id)));
+    entries
+}
+
+/// Move an installed addon out of AddOns into the quarantine.
+/// With `wtf_path`, its SavedVariables files are moved there as well.
+pub fn quarantine_addon(
+    flavor: GameFlavor,
+    addon_path: &Path,
+    local_name: &AddonFolderName,
+    wtf_path: Option<&Path>,
+) -> Result<QuarantinedAddon, String> {
+    let record = install_record::load_record(flavor, local_name);
+    quarantine_addon_in(&get_quarantine_dir(), flavor, addon_path, local_name, wtf_path, record, move_dir)
+}
+
+fn quarantine_addon_in(
+    quarantine_dir: &Path,
+    flavor: GameFlavor,
+    addon_path: &Path,
+    local_name: &AddonFolderName,
+    wtf_path: Option<&Path>,
+    record: Option<InstallRecord>,
+    move_files: MoveFn,
+) -> Result<QuarantinedAddon, String> {
+    let quarantined_at = install_record::now_timestamp();
+    let id = format!("{}_{}{}_{}", format_date(quarantined_at), quarantined_at, flavor.dir_name(), local_name);
+    let dir = quarantine_dir.join(&id);
+
+    let entry = QuarantinedAddon {
+        id,
+        local_name: local_name.clone(),
+        flavor,
+        version: read_addon_version(addon_path, local_name, flavor).ok(),
+        quarantined_at,
+        record,
+        saved_variables: wtf_path
+            .map(|wtf_path| saved_variables::find_files(wtf_path, local_name))
+            .unwrap_or_default(),
+    };
+
+    // Metadata first, so a folder in quarantine is always listed
+    fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create quarantine directory: {}", e))?;
+    let json = serde_json::to_string_pretty(&entry)
+        .map_err(|e| format!("Failed to serialize quarantine entry: {}", e))?;
+    fs::write(get_entry_meta_path(&dir), json)
+        .map_err(|e| format!("Failed to write quarantine entry: {}", e))?;
+
+    if let Some(wtf_path) = wtf_path {
+        let backup_dir = get_entry_saved_variables_path(&dir);
+        if let Err(e) = saved_variables::backup_and_remove(wtf_path, &entry.saved_variables, &backup_dir) {
+            let _ = saved_variables::restore(wtf_path, &entry.saved_variables, &backup_dir);
+            let _ = fs::remove_dir_all(&dir);
+            return Err(e);
+        }
+    }
+
+    match move_files(addon_path, &get_entry_files_path(&dir)) {
+        Ok(()) => {}
+        Err(MoveError::NotMoved(e)) => {
+            if let Some(wtf_path) = wtf_path {
+                let backup_dir = get_entry_saved_variables_path(&dir);
+                let _ = saved_variables::restore(wtf_path, &entry.saved_variables, &backup_dir);
+            }
+            let _ = fs::remove_dir_all(&dir);
+            return Err(format!("Failed to move addon to quarantine: {}", e));
+        }
+        // The quarantine now holds the only full copy, so it must stay
+        Err(MoveError::SourceLeft(e)) => {
+            return Err(format!(
+                "{} was moved to quarantine but could only be partly removed from AddOns ({}); \
+                 delete what is left of its folder, then restore it if needed",
+                local_name, e
+            ));
+        }
+    }
+
+    Ok(entry)
+}
+
+/// Move a quarantined addon and its SavedVariables back in place
+pub fn restore_entry(wow_path: &str, id: &str) -> Result<QuarantinedAddon, String> {
+    restore_entry_in(&get_quarantine_dir(), wow_path, id)
+}
+
+fn restore_entry_in(quarantine_dir: &Path, wow_path: &str, id: &str) -> Result<QuarantinedAddon, String> {
+    let entry = list_entries_in(quarantine_dir)
+        .into_iter()
+        .find(|entry| entry.id == id)
+        .ok_or_else(|| format!("Quarantined addon '{}' not found", id))?;
+
+    let dest_path = entry.flavor.addons_path(wow_path).join(&entry.local_name);
+    let wtf_path = entry.flavor.wtf_path(wow_path);
+    if dest_path.exists() {
+        return Err(format!(
+            "{} is installed again; uninstall it before restoring this copy",
+            entry.local_name
+        ));
+    }
+
+    let dir = quarantine_dir.join(&entry.id);
+    match move_dir(&get_entry_files_path(&dir), &dest_path) {
+        // Leftovers in the quarantine go with the entry below
+        Ok(()) | Err(MoveError::SourceLeft(_)) => {}
+        Err(MoveError::NotMoved(e)) => return Err(e),
+    }
+    saved_variables::restore(&wtf_path, &entry.saved_variables, &get_entry_saved_variables_path(&dir))?;
+
+    if let Some(record) = &entry.record {
+        install_record::save_record(record)?;
+    }
+
+    let _ = fs::remove_dir_all(&dir);
+
+    Ok(entry)
+}
+
+/// Delete quarantined addons older than `days`; 0 keeps them forever
+pub fn purge_expired(days: u64) {
+    purge_expired_in(&get_quarantine_dir(), days, install_record::now_timestamp());
+}
+
+fn purge_expired_in(quarantine_dir: &Path, days: u64, now: u64) {
+    if days == 0 {
+        return;
+    }
+
+    let cutoff = now.saturating_sub(days * SECONDS_PER_DAY);
+    for entry in list_entries_in(quarantine_dir) {
+        if entry.quarantined_at < cutoff {
+            let _ = fs::remove_dir_all(quarantine_dir.join(&entry.id));
+        }
+    }
+}
+
+/// List the uninstalled addons of a flavor that can still be restored
+#[tauri::command]
+pub fn list_quarantined(flavor: GameFlavor) -> Result<Vec<QuarantinedAddon>, String> {
+    Ok(list_entries()
+        .into_iter()
+        .filter(|entry| entry.flavor == flavor)
+        .collect())
+}
+
+/// Put an uninstalled addon back in place
+#[tauri::command]
+pub async fn restore_addon(app: tauri::AppHandle, wow_path: String, quarantine_id: String) -> Result<String, String> {
+    let entry = list_entries()
+        .into_iter()
+        .find(|entry| entry.id == quarantine_id)
+        .ok_or_else(|| format!("Quarantined addon '{}' not found", quarantine_id))?;
+
+    // Not while the same addon is being installed or rolled back
+    let _guard = app.state::<download::InstallRegistry>().begin(entry.flavor, &entry.local_name)?;
+
+    let entry = tokio::task::spawn_blocking(move || restore_entry(&wow_path, &quarantine_id))
+        .await
+        .map_err(|e| format!("Restore of {} failed: {}", entry.local_name, e))??;
+
+    Ok(format!("Successfully restored {}", entry.local_name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ADDON: &str = "Nihui_uf";
+
+    /// A WoW folder with one installed addon, and an empty quarantine
+    fn fixture() -> (tempfile::TempDir, String, PathBuf) {
+        let root = tempfile::tempdir().unwrap();
+        let wow_path = root.path().join("World of Warcraft").to_string_lossy().to_string();
+        let addon_path = GameFlavor::Retail.addons_path(&wow_path).join(ADDON);
+        fs::create_dir_all(&addon_path).unwrap();
+        fs::write(addon_path.join(format!("{}.toc", ADDON)), "## Interface: 110002\n## Version: 1.4.0\n").unwrap();
+        (root, wow_path, addon_path)
+    }
+
+    fn quarantine(quarantine_dir: &Path, addon_path: &Path) -> QuarantinedAddon {
+        let name = AddonFolderName::from_static(ADDON);
+        quarantine_addon_in(quarantine_dir, GameFlavor::Retail, addon_path, &name, None, None, move_dir).unwrap()
+    }
+
+    /// Copy across "volumes", then fail halfway through deleting the source
+    fn copy_then_fail_to_remove(from: &Path, to: &Path) -> Result<(), MoveError> {
+        copy_dir_all(from, to).map_err(MoveError::NotMoved)?;
+        fs::remove_file(from.join(format!("{}.toc", ADDON))).unwrap();
+        Err(MoveError::SourceLeft(format!("Failed to remove {}: permission denied", from.display())))
+    }
+
+    #[test]
+    fn quarantines_then_restores_an_addon() {
+        let (root, wow_path, addon_path) = fixture();
+        let quarantine_dir = root.path().join("quarantine");
+
+        let entry = quarantine(&quarantine_dir, &addon_path);
+
+        assert!(!addon_path.exists());
+        assert_eq!(entry.version.as_deref(), Some("1.4.0"));
+        let listed = list_entries_in(&quarantine_dir);
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].id, entry.id);
+
+        restore_entry_in(&quarantine_dir, &wow_path, &entry.id).unwrap();
+
+        assert!(addon_path.join(format!("{}.toc", ADDON)).exists());
+        assert!(list_entries_in(&quarantine_dir).is_empty());
+    }
+
+    #[test]
+    fn does_not_restore_over_a_reinstalled_addon() {
+        let (root, wow_path, addon_path) = fixture();
+        let quarantine_dir = root.path().join("quarantine");
+
+        let entry = quarantine(&quarantine_dir, &addon_path);
+        fs::create_dir_all(&addon_path).unwrap();
+
+        assert!(restore_entry_in(&quarantine_dir, &wow_path, &entry.id).is_err());
+        assert_eq!(list_entries_in(&quarantine_dir).len(), 1);
+    }
+
+    #[test]
+    fn keeps_the_quarantined_copy_when_the_source_is_only_partly_removed() {
+        let (root, _wow_path, addon_path) = fixture();
+        let quarantine_dir = root.path().join("quarantine");
+        fs::write(addon_path.join("core.lua"), "print('hi')\n").unwrap();
+        let name = AddonFolderName::from_static(ADDON);
+
+        let result = quarantine_addon_in(
+            &quarantine_dir,
+            GameFlavor::Retail,
+            &addon_path,
+            &name,
+            None,
+            None,
+            copy_then_fail_to_remove,
+        );
+
+        assert!(result.unwrap_err().contains("partly removed"));
+        let listed = list_entries_in(&quarantine_dir);
+        assert_eq!(listed.len(), 1);
+        let files = get_entry_files_path(&quarantine_dir.join(&listed[0].id));
+        assert!(files.join(format!("{}.toc", ADDON)).exists());
+        assert!(files.join("core.lua").exists());
+    }
+
+    #[test]
+    fn purges_only_expired_entries() {
+        let (root, _wow_path, addon_path) = fixture();
+        let quarantine_dir = root.path().join("quarantine");
+        let entry = quarantine(&quarantine_dir, &addon_path);
+        let now = entry.quarantined_at;
+
+        // Kept forever with 0, and while younger than the retention
+        purge_expired_in(&quarantine_dir, 0, now + 365 * SECONDS_PER_DAY);
+        purge_expired_in(&quarantine_dir, 7, now + 6 * SECONDS_PER_DAY);
+        assert_eq!(list_entries_in(&quarantine_dir).len(), 1);
+
+        purge_expired_in(&quarantine_dir, 7, now + 8 * SECONDS_PER_DAY);
+        assert!(list_entries_in(&quarantine_dir).is_empty());
+        assert!(!quarantine_dir.join(&entry.id).exists());
+    }
+
+    #[test]
+    fn formats_dates_in_utc() {
+        assert_eq!(format_date(0), "1970-01-01");
+        assert_eq!(format_date(1_709_208_000), "2024-02-29");
+    }
+}
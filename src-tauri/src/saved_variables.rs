@@ -0,0 +1,204 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::addon_name::AddonFolderName;
+use crate::flavor::GameFlavor;
+
+// ===========================
+// SAVEDVARIABLES CLEANUP
+// ===========================
+
+/// A SavedVariables file written by the game for an addon
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct SavedVariablesFile {
+    pub path: String, // Relative to the WTF folder, '/' separated
+    pub account: String,
+    pub realm: Option<String>, // None for account-wide SavedVariables
+    pub character: Option<String>,
+}
+
+/// True for `<addon>.lua` and the `<addon>.lua.bak` the game keeps next to it
+fn belongs_to(file_name: &str, local_name: &str) -> bool {
+    let lower = file_name.to_ascii_lowercase();
+    let addon = local_name.to_ascii_lowercase();
+    lower == format!("{}.lua", addon) || lower == format!("{}.lua.bak", addon)
+}
+
+fn sub_dirs(path: &Path) -> Vec<(String, PathBuf)> {
+    let mut dirs: Vec<(String, PathBuf)> = fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|entry| entry.path().is_dir())
+                .map(|entry| (entry.file_name().to_string_lossy().to_string(), entry.path()))
+                .collect()
+        })
+        .unwrap_or_default();
+    dirs.sort();
+    dirs
+}
+
+fn files_in(
+    saved_variables_dir: &Path,
+    local_name: &str,
+    account: &str,
+    realm: Option<&str>,
+    character: Option<&str>,
+    files: &mut Vec<SavedVariablesFile>,
+) {
+    let Ok(entries) = fs::read_dir(saved_variables_dir) else {
+        return;
+    };
+
+    let mut names: Vec<String> = entries
+        .flatten()
+        .filter(|entry| entry.path().is_file())
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .filter(|name| belongs_to(name, local_name))
+        .collect();
+    names.sort();
+
+    for name in names {
+        let path = match (realm, character) {
+            (Some(realm), Some(character)) => {
+                format!("Account/{}/{}/{}/SavedVariables/{}", account, realm, character, name)
+            }
+            _ => format!("Account/{}/SavedVariables/{}", account, name),
+        };
+        files.push(SavedVariablesFile {
+            path,
+            account: account.to_string(),
+            realm: realm.map(str::to_string),
+            character: character.map(str::to_string),
+        });
+    }
+}
+
+/// Every SavedVariables file of an addon, across accounts, realms and characters.
+/// Layout: WTF/Account/<account>/SavedVariables and
+/// WTF/Account/<account>/<realm>/<character>/SavedVariables
+pub fn find_files(wtf_path: &Path, local_name: &str) -> Vec<SavedVariablesFile> {
+    let mut files = Vec::new();
+
+    for (account, account_path) in sub_dirs(&wtf_path.join("Account")) {
+        for (realm, realm_path) in sub_dirs(&account_path) {
+            if realm.eq_ignore_ascii_case("SavedVariables") {
+                files_in(&realm_path, local_name, &account, None, None, &mut files);
+                continue;
+            }
+            for (character, character_path) in sub_dirs(&realm_path) {
+                files_in(
+                    &character_path.join("SavedVariables"),
+                    local_name,
+                    &account,
+                    Some(&realm),
+                    Some(&character),
+                    &mut files,
+                );
+            }
+        }
+    }
+
+    files
+}
+
+/// Copy the files into `backup_dir` (same relative paths), then delete them.
+/// Nothing is deleted unless every file was backed up.
+pub fn backup_and_remove(wtf_path: &Path, files: &[SavedVariablesFile], backup_dir: &Path) -> Result<(), String> {
+    for file in files {
+        let backup_path = backup_dir.join(&file.path);
+        if let Some(parent) = backup_path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create backup directory: {}", e))?;
+        }
+        fs::copy(wtf_path.join(&file.path), &backup_path)
+            .map_err(|e| format!("Failed to back up {}: {}", file.path, e))?;
+    }
+
+    for file in files {
+        fs::remove_file(wtf_path.join(&file.path))
+            .map_err(|e| format!("Failed to remove {}: {}", file.path, e))?;
+    }
+
+    Ok(())
+}
+
+/// Put backed up files back, skipping any the game has written again since
+pub fn restore(wtf_path: &Path, files: &[SavedVariablesFile], backup_dir: &Path) -> Result<(), String> {
+    for file in files {
+        let dest = wtf_path.join(&file.path);
+        if dest.exists() {
+            continue;
+        }
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create directory: {}", e))?;
+        }
+        fs::copy(backup_dir.join(&file.path), &dest)
+            .map_err(|e| format!("Failed to restore {}: {}", file.path, e))?;
+    }
+
+    Ok(())
+}
+
+/// List the SavedVariables files an uninstall would remove
+#[tauri::command]
+pub fn list_saved_variables(
+    wow_path: String,
+    flavor: GameFlavor,
+    addon_name: AddonFolderName,
+) -> Result<Vec<SavedVariablesFile>, String> {
+    Ok(find_files(&flavor.wtf_path(&wow_path), &addon_name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn touch(path: &Path) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, "NihuiDB = {}").unwrap();
+    }
+
+    #[test]
+    fn finds_account_and_character_files() {
+        let wtf = tempfile::tempdir().unwrap();
+        let root = wtf.path();
+        touch(&root.join("Account/ACC1/SavedVariables/Nihui_uf.lua"));
+        touch(&root.join("Account/ACC1/SavedVariables/Nihui_uf.lua.bak"));
+        touch(&root.join("Account/ACC1/SavedVariables/Nihui_uf_Options.lua"));
+        touch(&root.join("Account/ACC1/Hyjal/Thrall/SavedVariables/nihui_uf.lua"));
+        touch(&root.join("Account/ACC2/Ysondre/Jaina/SavedVariables/Details.lua"));
+
+        let files = find_files(root, "Nihui_uf");
+        let paths: Vec<&str> = files.iter().map(|f| f.path.as_str()).collect();
+
+        assert_eq!(
+            paths,
+            vec![
+                "Account/ACC1/Hyjal/Thrall/SavedVariables/nihui_uf.lua",
+                "Account/ACC1/SavedVariables/Nihui_uf.lua",
+                "Account/ACC1/SavedVariables/Nihui_uf.lua.bak",
+            ]
+        );
+        assert_eq!(files[0].realm.as_deref(), Some("Hyjal"));
+        assert_eq!(files[0].character.as_deref(), Some("Thrall"));
+        assert_eq!(files[1].realm, None);
+    }
+
+    #[test]
+    fn backup_then_restore_round_trips() {
+        let wtf = tempfile::tempdir().unwrap();
+        let backup = tempfile::tempdir().unwrap();
+        touch(&wtf.path().join("Account/ACC1/SavedVariables/Nihui_uf.lua"));
+
+        let files = find_files(wtf.path(), "Nihui_uf");
+        backup_and_remove(wtf.path(), &files, backup.path()).unwrap();
+        assert!(find_files(wtf.path(), "Nihui_uf").is_empty());
+        assert!(backup.path().join("Account/ACC1/SavedVariables/Nihui_uf.lua").exists());
+
+        restore(wtf.path(), &files, backup.path()).unwrap();
+        assert_eq!(find_files(wtf.path(), "Nihui_uf"), files);
+    }
+}
@@ -0,0 +1,340 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{Emitter, Manager};
+use tokio::sync::Notify;
+
+use crate::build_info::{self, BuildInfo};
+use crate::flavor::{self, GameFlavor};
+use crate::http::SharedHttpClient;
+use crate::quarantine;
+use crate::update_check::{self, AddonCheckResult, AddonStatus};
+use crate::{get_app_data_dir, load_config, resolve_addon_list, update_config};
+
+// ===========================
+// BACKGROUND UPDATE SCHEDULER
+// ===========================
+
+/// Default time between two background checks
+pub const DEFAULT_CHECK_INTERVAL_MINUTES: u64 = 60;
+
+/// Delay before the first check after startup, so launching stays fast
+const STARTUP_DELAY: Duration = Duration::from_secs(60);
+
+/// How often the game build is looked at between two checks, to catch patch days
+const PATCH_POLL_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// Maximum random offset added to each interval, as a fraction of it
+const JITTER_RATIO: f64 = 0.1;
+
+/// Event emitted with the results of every background check
+pub const UPDATE_CHECK_EVENT: &str = "update-check-completed";
+
+/// Persisted scheduler state, survives restarts
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct SchedulerState {
+    pub last_check: u64, // Unix timestamp (seconds), 0 = never
+    #[serde(default)]
+    pub available_updates: Vec<String>, // Addons with updates at the last check, with their flavor outside retail
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct UpdateCheckEvent {
+    pub checked_at: u64,
+    pub results: Vec<AddonCheckResult>,
+}
+
+/// Handle kept in Tauri managed state to wake the scheduler early
+#[derive(Default)]
+pub struct SchedulerHandle {
+    wake: Arc<Notify>,
+}
+
+impl SchedulerHandle {
+    /// Run a check now instead of waiting for the next tick
+    pub fn trigger(&self) {
+        self.wake.notify_one();
+    }
+}
+
+fn get_state_path() -> PathBuf {
+    get_app_data_dir().join("scheduler.json")
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+pub fn load_state() -> SchedulerState {
+    fs::read_to_string(get_state_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_state(state: &SchedulerState) -> Result<(), String> {
+    let path = get_state_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create scheduler directory: {}", e))?;
+    }
+
+    let json = serde_json::to_string_pretty(state)
+        .map_err(|e| format!("Failed to serialize scheduler state: {}", e))?;
+
+    fs::write(&path, json)
+        .map_err(|e| format!("Failed to write scheduler state: {}", e))?;
+
+    Ok(())
+}
+
+/// Pseudo-random value in [0, 1), good enough to spread checks over time
+fn jitter_factor() -> f64 {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or(0);
+    f64::from(nanos % 1_000_000) / 1_000_000.0
+}
+
+/// Time to wait before the next check.
+/// `jitter` is in [0, 1) and stretches the interval by up to `JITTER_RATIO`.
+pub fn next_check_delay(last_check: u64, now: u64, interval: Duration, jitter: f64) -> Duration {
+    let jittered = interval.as_secs_f64() * (1.0 + JITTER_RATIO * jitter.clamp(0.0, 1.0));
+    let due = last_check as f64 + jittered;
+    let remaining = (due - now as f64).max(0.0);
+
+    Duration::from_secs_f64(remaining).max(STARTUP_DELAY.min(interval))
+}
+
+fn updates_in(results: &[AddonCheckResult]) -> Vec<String> {
+    let mut names: Vec<String> = results
+        .iter()
+        .filter(|result| matches!(result.status, AddonStatus::UpdateAvailable { .. }))
+        .map(|result| match result.flavor {
+            GameFlavor::Retail => result.local_name.clone(),
+            flavor => format!("{} ({})", result.local_name, flavor.label()),
+        })
+        .collect();
+    names.sort();
+    names
+}
+
+fn notify_updates(app: &tauri::AppHandle, addon_names: &[String]) {
+    use tauri_plugin_notification::NotificationExt;
+
+    let addon_list = addon_names.join(", ");
+    let body = if addon_names.len() == 1 {
+        format!("{} has an update available. Click the tray icon to view.", addon_list)
+    } else {
+        format!(
+            "{} addons have updates available: {}. Click the tray icon to view.",
+            addon_names.len(),
+            addon_list
+        )
+    };
+
+    let _ = app
+        .notification()
+        .builder()
+        .title("🔔 Nihui Addons Update Available")
+        .body(body)
+        .show();
+}
+
+/// Client builds of the configured installation, empty when unknown
+fn installed_builds() -> Vec<BuildInfo> {
+    load_config()
+        .ok()
+        .and_then(|config| config.wow_path)
+        .and_then(|wow_path| build_info::read_build_info(&wow_path).ok())
+        .unwrap_or_default()
+}
+
+/// Builds of the flavors patched since the last successful check
+fn detect_patches(builds: &[BuildInfo]) -> Vec<BuildInfo> {
+    match load_config() {
+        Ok(config) => build_info::changed_builds(&config.last_seen_builds, builds),
+        Err(_) => Vec::new(),
+    }
+}
+
+/// Remember the builds a successful check ran against
+fn remember_builds(builds: &[BuildInfo]) {
+    let Ok(config) = load_config() else {
+        return;
+    };
+
+    let current: HashMap<GameFlavor, u32> = builds.iter().map(|info| (info.flavor, info.build)).collect();
+    if current.iter().any(|(flavor, build)| config.last_seen_builds.get(flavor) != Some(build)) {
+        let _ = update_config(|config| {
+            config.last_seen_builds.extend(current);
+            Ok(())
+        });
+    }
+}
+
+fn notify_game_patched(app: &tauri::AppHandle, patched: &[BuildInfo], results: &[AddonCheckResult]) {
+    use tauri_plugin_notification::NotificationExt;
+
+    let clients = patched
+        .iter()
+        .map(|info| format!("{} {}", info.flavor.label(), info.version))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let mut outdated: Vec<String> = results
+        .iter()
+        .filter(|result| result.interface_outdated && patched.iter().any(|info| info.flavor == result.flavor))
+        .map(|result| match result.flavor {
+            GameFlavor::Retail => result.local_name.clone(),
+            flavor => format!("{} ({})", result.local_name, flavor.label()),
+        })
+        .collect();
+    outdated.sort();
+
+    let body = if outdated.is_empty() {
+        format!("Updated to {}. Every addon targets the new version.", clients)
+    } else {
+        format!(
+            "Updated to {}. {} addon(s) still target the old version: {}.",
+            clients,
+            outdated.len(),
+            outdated.join(", ")
+        )
+    };
+
+    let _ = app
+        .notification()
+        .builder()
+        .title("🎮 World of Warcraft Patched")
+        .body(body)
+        .show();
+}
+
+/// Run one full check of every installed flavor, emit the results and
+/// notify when the set of available updates changed since the previous check
+pub async fn run_check(app: &tauri::AppHandle) -> Result<Vec<AddonCheckResult>, String> {
+    let config = load_config()?;
+    let wow_path = config.wow_path.clone().ok_or("WoW path not configured")?;
+    let http = app.state::<SharedHttpClient>().client();
+
+    let mut results = Vec::new();
+    for flavor in flavor::detect_flavors(&PathBuf::from(&wow_path)) {
+        results.extend(
+            update_check::check_all(
+                &http,
+                &wow_path,
+                flavor,
+                resolve_addon_list(&config, flavor),
+                config.check_concurrency,
+            )
+            .await,
+        );
+    }
+
+    let mut state = load_state();
+    let updates = updates_in(&results);
+
+    if !updates.is_empty() && updates != state.available_updates {
+        notify_updates(app, &updates);
+    }
+
+    state.last_check = now_secs();
+    state.available_updates = updates;
+    save_state(&state)?;
+
+    let _ = app.emit(
+        UPDATE_CHECK_EVENT,
+        UpdateCheckEvent {
+            checked_at: state.last_check,
+            results: results.clone(),
+        },
+    );
+
+    Ok(results)
+}
+
+/// Start the scheduler loop on the Tauri async runtime
+pub fn start(app: tauri::AppHandle) {
+    let handle = SchedulerHandle::default();
+    let wake = handle.wake.clone();
+    app.manage(handle);
+
+    tauri::async_runtime::spawn(async move {
+        loop {
+            // Long-running tray apps would otherwise only purge at startup
+            quarantine::purge_expired(load_config().unwrap_or_default().quarantine_days);
+
+            // A game patch triggers a check right away, startup included
+            let builds = installed_builds();
+            let patched = detect_patches(&builds);
+
+            if patched.is_empty() {
+                let config = load_config().unwrap_or_default();
+
+                // 0 disables background checks until the settings change
+                let due = async {
+                    if config.check_interval_minutes == 0 {
+                        std::future::pending::<()>().await;
+                    }
+                    let interval = Duration::from_secs(config.check_interval_minutes * 60);
+                    let delay = next_check_delay(load_state().last_check, now_secs(), interval, jitter_factor());
+
+                    // Background checks are not urgent: with the GitHub quota
+                    // nearly gone, wait until it resets
+                    let rate_limit_delay = app
+                        .state::<SharedHttpClient>()
+                        .client()
+                        .rate_limit()
+                        .map(|rate_limit| rate_limit.defer_delay(now_secs()))
+                        .unwrap_or_default();
+
+                    tokio::time::sleep(delay.max(rate_limit_delay)).await;
+                };
+
+                tokio::select! {
+                    _ = due => {}
+                    _ = wake.notified() => {}
+                    _ = tokio::time::sleep(PATCH_POLL_INTERVAL) => continue,
+                }
+            }
+
+            match run_check(&app).await {
+                Ok(results) => {
+                    // Only remembered now, so a failed check reports the patch again
+                    remember_builds(&builds);
+                    if !patched.is_empty() {
+                        notify_game_patched(&app, &patched, &results);
+                    }
+                }
+                // The patch is still pending: retry later rather than right away
+                Err(_) if !patched.is_empty() => {
+                    tokio::select! {
+                        _ = wake.notified() => {}
+                        _ = tokio::time::sleep(PATCH_POLL_INTERVAL) => {}
+                    }
+                }
+                Err(_) => {}
+            }
+        }
+    });
+}
+
+/// Run a background check right away
+#[tauri::command]
+pub fn trigger_update_check(scheduler: tauri::State<'_, SchedulerHandle>) {
+    scheduler.trigger();
+}
+
+/// Get the persisted scheduler state (last check time, pending updates)
+#[tauri::command]
+pub fn get_scheduler_state() -> SchedulerState {
+    load_state()
+}
@@ -0,0 +1,252 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use crate::{get_app_data_dir, load_config, write_config, AppConfig};
+
+// ===========================
+// SECRET STORAGE
+// ===========================
+
+/// Key of the GitHub token in the secret store
+pub const GITHUB_TOKEN_KEY: &str = "github_token";
+
+/// Service name the secrets are filed under in the OS keyring
+const KEYRING_SERVICE: &str = "nihui_app";
+
+/// Set to "file" to keep secrets in a file instead of the OS keyring
+const SECRET_STORE_ENV: &str = "NIHUI_SECRET_STORE";
+
+/// Where credentials are kept
+pub trait SecretStore: Send + Sync {
+    fn get(&self, key: &str) -> Result<Option<String>, String>;
+    fn set(&self, key: &str, value: &str) -> Result<(), String>;
+    fn delete(&self, key: &str) -> Result<(), String>;
+}
+
+/// The OS keyring: Windows Credential Manager, macOS Keychain or the Secret Service on Linux
+pub struct KeyringStore;
+
+impl KeyringStore {
+    fn entry(key: &str) -> Result<keyring::Entry, String> {
+        keyring::Entry::new(KEYRING_SERVICE, key).map_err(|e| format!("Failed to open keyring entry: {}", e))
+    }
+}
+
+impl SecretStore for KeyringStore {
+    fn get(&self, key: &str) -> Result<Option<String>, String> {
+        match Self::entry(key)?.get_password() {
+            Ok(value) => Ok(Some(value)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(format!("Failed to read from keyring: {}", e)),
+        }
+    }
+
+    fn set(&self, key: &str, value: &str) -> Result<(), String> {
+        Self::entry(key)?.set_password(value).map_err(|e| {
+            format!(
+                "Failed to write to keyring: {} (set {}=file to keep secrets in a file instead)",
+                e, SECRET_STORE_ENV
+            )
+        })
+    }
+
+    fn delete(&self, key: &str) -> Result<(), String> {
+        match Self::entry(key)?.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(format!("Failed to delete from keyring: {}", e)),
+        }
+    }
+}
+
+/// Secrets in a JSON file only the user can read, for tests and systems
+/// without a keyring
+pub struct FileStore {
+    path: PathBuf,
+    lock: Mutex<()>, // Serializes read-modify-write cycles
+}
+
+impl FileStore {
+    pub fn new(path: PathBuf) -> Self {
+        FileStore {
+            path,
+            lock: Mutex::new(()),
+        }
+    }
+
+    fn read(&self) -> BTreeMap<String, String> {
+        fs::read_to_string(&self.path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    fn write(&self, secrets: &BTreeMap<String, String>) -> Result<(), String> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create secrets directory: {}", e))?;
+        }
+
+        let json = serde_json::to_string_pretty(secrets)
+            .map_err(|e| format!("Failed to serialize secrets: {}", e))?;
+
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+        let mut file = options
+            .open(&self.path)
+            .map_err(|e| format!("Failed to open secrets file: {}", e))?;
+
+        // The mode only applies to new files, tighten an existing one too
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&self.path, fs::Permissions::from_mode(0o600))
+                .map_err(|e| format!("Failed to restrict secrets file: {}", e))?;
+        }
+
+        file.write_all(json.as_bytes())
+            .map_err(|e| format!("Failed to write secrets file: {}", e))
+    }
+
+    fn update(&self, change: impl FnOnce(&mut BTreeMap<String, String>)) -> Result<(), String> {
+        let _guard = self.lock.lock().map_err(|_| "Secrets file lock poisoned".to_string())?;
+        let mut secrets = self.read();
+        change(&mut secrets);
+        self.write(&secrets)
+    }
+}
+
+impl SecretStore for FileStore {
+    fn get(&self, key: &str) -> Result<Option<String>, String> {
+        Ok(self.read().remove(key))
+    }
+
+    fn set(&self, key: &str, value: &str) -> Result<(), String> {
+        self.update(|secrets| {
+            secrets.insert(key.to_string(), value.to_string());
+        })
+    }
+
+    fn delete(&self, key: &str) -> Result<(), String> {
+        self.update(|secrets| {
+            secrets.remove(key);
+        })
+    }
+}
+
+/// Whether `NIHUI_SECRET_STORE` asks for the file store
+fn file_store_requested(value: Option<&str>) -> bool {
+    value.is_some_and(|value| value.trim().eq_ignore_ascii_case("file"))
+}
+
+/// The OS keyring, or a file in the app data directory when
+/// `NIHUI_SECRET_STORE=file` is set. Never falls back to the file on its
+/// own: without a keyring, saving a secret fails and says so.
+pub fn default_store() -> Box<dyn SecretStore> {
+    if file_store_requested(std::env::var(SECRET_STORE_ENV).ok().as_deref()) {
+        Box::new(FileStore::new(get_app_data_dir().join("secrets.json")))
+    } else {
+        Box::new(KeyringStore)
+    }
+}
+
+/// Move the plain-text token of `config` to the secret store. Returns
+/// whether the config has to be rewritten to drop it.
+fn migrate_token(config: &AppConfig, store: &dyn SecretStore) -> Result<bool, String> {
+    let Some(token) = config.github_token.as_deref().map(str::trim) else {
+        return Ok(false);
+    };
+
+    if !token.is_empty() {
+        store.set(GITHUB_TOKEN_KEY, token)?;
+    }
+    Ok(true)
+}
+
+/// Move a token saved in plain text in config.json by earlier versions to the
+/// secret store. The config is rewritten without it only once it is stored.
+pub fn migrate_config_token(store: &dyn SecretStore) -> Result<(), String> {
+    let config = load_config()?;
+    if migrate_token(&config, store)? {
+        // github_token is never serialized, so writing the config drops it
+        write_config(&config)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_store_round_trips() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = FileStore::new(dir.path().join("secrets.json"));
+
+        assert_eq!(store.get(GITHUB_TOKEN_KEY).unwrap(), None);
+
+        store.set(GITHUB_TOKEN_KEY, "ghp_test").unwrap();
+        store.set("other", "value").unwrap();
+        assert_eq!(store.get(GITHUB_TOKEN_KEY).unwrap().as_deref(), Some("ghp_test"));
+
+        store.delete(GITHUB_TOKEN_KEY).unwrap();
+        assert_eq!(store.get(GITHUB_TOKEN_KEY).unwrap(), None);
+        assert_eq!(store.get("other").unwrap().as_deref(), Some("value"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn file_store_is_private_to_the_user() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("secrets.json");
+        fs::write(&path, "{}").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+
+        FileStore::new(path.clone()).set(GITHUB_TOKEN_KEY, "ghp_test").unwrap();
+
+        assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+    }
+
+    #[test]
+    fn file_store_is_opt_in() {
+        assert!(file_store_requested(Some("file")));
+        assert!(file_store_requested(Some(" FILE ")));
+        assert!(!file_store_requested(Some("keyring")));
+        assert!(!file_store_requested(None));
+    }
+
+    #[test]
+    fn migrates_the_plain_text_token() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = FileStore::new(dir.path().join("secrets.json"));
+
+        let config = AppConfig {
+            github_token: Some(" ghp_legacy ".to_string()),
+            ..AppConfig::default()
+        };
+        assert_eq!(migrate_token(&config, &store), Ok(true));
+        assert_eq!(store.get(GITHUB_TOKEN_KEY).unwrap().as_deref(), Some("ghp_legacy"));
+
+        // The rewritten config no longer holds it
+        let json = serde_json::to_value(&config).unwrap();
+        assert!(json.get("github_token").is_none());
+
+        // An empty token is dropped without touching the store
+        let empty = AppConfig {
+            github_token: Some(String::new()),
+            ..AppConfig::default()
+        };
+        store.delete(GITHUB_TOKEN_KEY).unwrap();
+        assert_eq!(migrate_token(&empty, &store), Ok(true));
+        assert_eq!(store.get(GITHUB_TOKEN_KEY).unwrap(), None);
+
+        assert_eq!(migrate_token(&AppConfig::default(), &store), Ok(false));
+    }
+}
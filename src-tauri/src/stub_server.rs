@@ -0,0 +1,189 @@
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+// ===========================
+// STUB HTTP SERVER (TESTS)
+// ===========================
+
+/// Canned answer of the stub server
+pub struct StubResponse {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+    content_length: Option<usize>, // Announced length, when more than the body
+    answered: bool, // False = read the request, then never answer
+}
+
+impl StubResponse {
+    pub fn json(body: serde_json::Value) -> Self {
+        StubResponse {
+            status: 200,
+            headers: vec![("Content-Type".to_string(), "application/json".to_string())],
+            body: body.to_string().into_bytes(),
+            content_length: None,
+            answered: true,
+        }
+    }
+
+    pub fn text(body: &str) -> Self {
+        StubResponse {
+            status: 200,
+            headers: vec![("Content-Type".to_string(), "text/plain".to_string())],
+            body: body.as_bytes().to_vec(),
+            content_length: None,
+            answered: true,
+        }
+    }
+
+    /// Empty response with this status
+    pub fn status(status: u16) -> Self {
+        StubResponse {
+            status,
+            headers: Vec::new(),
+            body: Vec::new(),
+            content_length: None,
+            answered: true,
+        }
+    }
+
+    /// Accept the request and keep the connection open without answering
+    pub fn no_answer() -> Self {
+        StubResponse {
+            answered: false,
+            ..StubResponse::status(200)
+        }
+    }
+
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Send `body` while announcing `content_length` bytes, then keep the
+    /// connection open without sending anything else
+    pub fn stalled_body(mut self, body: &[u8], content_length: usize) -> Self {
+        self.body = body.to_vec();
+        self.content_length = Some(content_length);
+        self
+    }
+}
+
+/// A request received by the stub server
+pub struct StubRequest {
+    pub path: String,
+    pub headers: Vec<(String, String)>, // Names in lowercase
+    pub body: String,
+    pub at: Instant,
+}
+
+impl StubRequest {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// HTTP server on a local port answering each connection with the next
+/// canned response, in order
+pub struct StubServer {
+    pub url: String,
+    requests: Arc<Mutex<Vec<StubRequest>>>,
+}
+
+impl StubServer {
+    pub async fn start(responses: Vec<StubResponse>) -> StubServer {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+
+        let received = requests.clone();
+        tokio::spawn(async move {
+            for response in responses {
+                let Ok((mut socket, _)) = listener.accept().await else { return };
+                let request = read_request(&mut socket).await;
+                received.lock().unwrap().push(request);
+                if response.answered {
+                    write_response(&mut socket, &response).await;
+                }
+                if response.content_length.is_some() || !response.answered {
+                    // Stall: hold the connection open
+                    tokio::spawn(async move {
+                        tokio::time::sleep(std::time::Duration::from_secs(60)).await;
+                        drop(socket);
+                    });
+                }
+            }
+        });
+
+        StubServer { url, requests }
+    }
+
+    /// Requests received so far, in order
+    pub fn requests(&self) -> std::sync::MutexGuard<'_, Vec<StubRequest>> {
+        self.requests.lock().unwrap()
+    }
+}
+
+async fn read_request(socket: &mut TcpStream) -> StubRequest {
+    let at = Instant::now();
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 4096];
+
+    let header_end = loop {
+        if let Some(end) = buffer.windows(4).position(|window| window == b"\r\n\r\n") {
+            break end + 4;
+        }
+        let read = socket.read(&mut chunk).await.unwrap();
+        if read == 0 {
+            break buffer.len();
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+    };
+
+    let head = String::from_utf8_lossy(&buffer[..header_end]).to_string();
+    let headers: Vec<(String, String)> = head
+        .lines()
+        .skip(1)
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_ascii_lowercase(), value.trim().to_string()))
+        .collect();
+    let content_length = headers
+        .iter()
+        .find(|(name, _)| name == "content-length")
+        .and_then(|(_, value)| value.parse::<usize>().ok())
+        .unwrap_or(0);
+
+    while buffer.len() < header_end + content_length {
+        let read = socket.read(&mut chunk).await.unwrap();
+        if read == 0 {
+            break;
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+    }
+
+    StubRequest {
+        path: head.split_whitespace().nth(1).unwrap_or_default().to_string(),
+        headers,
+        body: String::from_utf8_lossy(&buffer[header_end..]).to_string(),
+        at,
+    }
+}
+
+async fn write_response(socket: &mut TcpStream, response: &StubResponse) {
+    let mut head = format!("HTTP/1.1 {} Stub\r\nConnection: close\r\n", response.status);
+    for (name, value) in &response.headers {
+        head.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    let content_length = response.content_length.unwrap_or(response.body.len());
+    head.push_str(&format!("Content-Length: {}\r\n\r\n", content_length));
+
+    socket.write_all(head.as_bytes()).await.unwrap();
+    socket.write_all(&response.body).await.unwrap();
+    if response.content_length.is_none() {
+        socket.shutdown().await.ok();
+    }
+}
@@ -0,0 +1,350 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::flavor::GameFlavor;
+use crate::{copy_dir_all, get_app_data_dir};
+
+// ===========================
+// ATOMIC ADDON REPLACEMENT
+// ===========================
+
+/// Folder next to AddOns where new versions are staged before the swap.
+/// Same volume as AddOns, so the final renames are atomic.
+const STAGING_DIR: &str = ".nihui_staging";
+
+/// Folder next to AddOns where the previous version waits during the swap
+const ASIDE_DIR: &str = ".nihui_aside";
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+enum SwapStep {
+    Staged, // New version copied to staging, AddOns untouched
+    OldMovedAside, // Old folder renamed aside, new one not in place yet
+    Swapped, // New folder in place, old one can be deleted
+}
+
+/// On-disk record of a swap in progress, used to recover after a crash
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct SwapJournal {
+    addon: String,
+    #[serde(default)]
+    flavor: GameFlavor,
+    dest: PathBuf,
+    staging: PathBuf,
+    aside: PathBuf,
+    step: SwapStep,
+}
+
+fn get_journal_dir() -> PathBuf {
+    get_app_data_dir().join("swap_journal")
+}
+
+fn get_journal_path(journal_dir: &Path, flavor: GameFlavor, addon: &str) -> PathBuf {
+    journal_dir.join(format!("{}{}.json", flavor.dir_name(), addon))
+}
+
+fn write_journal(journal_dir: &Path, journal: &SwapJournal) -> Result<(), String> {
+    fs::create_dir_all(journal_dir)
+        .map_err(|e| format!("Failed to create swap journal directory: {}", e))?;
+
+    let json = serde_json::to_string_pretty(journal)
+        .map_err(|e| format!("Failed to serialize swap journal: {}", e))?;
+
+    // Write then rename so a crash never leaves a half-written journal
+    let path = get_journal_path(journal_dir, journal.flavor, &journal.addon);
+    let tmp_path = path.with_extension("json.tmp");
+    fs::write(&tmp_path, json)
+        .map_err(|e| format!("Failed to write swap journal: {}", e))?;
+    fs::rename(&tmp_path, &path)
+        .map_err(|e| format!("Failed to write swap journal: {}", e))?;
+
+    Ok(())
+}
+
+fn remove_journal(journal_dir: &Path, flavor: GameFlavor, addon: &str) {
+    let _ = fs::remove_file(get_journal_path(journal_dir, flavor, addon));
+}
+
+fn remove_if_exists(path: &Path) -> Result<(), String> {
+    if path.exists() {
+        fs::remove_dir_all(path)
+            .map_err(|e| format!("Failed to remove {}: {}", path.display(), e))?;
+    }
+    Ok(())
+}
+
+/// Replace `addons_path/<addon>` with the contents of `source`.
+/// The old folder is restored if any step fails.
+pub fn replace_addon_dir(
+    source: &Path,
+    addons_path: &Path,
+    flavor: GameFlavor,
+    addon: &str,
+) -> Result<PathBuf, String> {
+    replace_addon_dir_in(&get_journal_dir(), source, addons_path, flavor, addon)
+}
+
+fn replace_addon_dir_in(
+    journal_dir: &Path,
+    source: &Path,
+    addons_path: &Path,
+    flavor: GameFlavor,
+    addon: &str,
+) -> Result<PathBuf, String> {
+    let interface_path = addons_path
+        .parent()
+        .ok_or("AddOns directory has no parent")?;
+
+    let journal = SwapJournal {
+        addon: addon.to_string(),
+        flavor,
+        dest: addons_path.join(addon),
+        staging: interface_path.join(STAGING_DIR).join(addon),
+        aside: interface_path.join(ASIDE_DIR).join(addon),
+        step: SwapStep::Staged,
+    };
+
+    // Leftovers from an earlier attempt
+    remove_if_exists(&journal.staging)?;
+    remove_if_exists(&journal.aside)?;
+
+    // Stage the new version; AddOns is untouched if this fails
+    if let Err(e) = copy_dir_all(source, &journal.staging) {
+        let _ = remove_if_exists(&journal.staging);
+        return Err(e);
+    }
+
+    if let Err(e) = write_journal(journal_dir, &journal) {
+        let _ = remove_if_exists(&journal.staging);
+        return Err(e);
+    }
+
+    match swap_into_place(journal_dir, journal.clone()) {
+        Ok(()) => {
+            // The new version is live; failing to clean up is not an error
+            let _ = remove_if_exists(&journal.aside);
+            remove_journal(journal_dir, flavor, addon);
+            Ok(journal.dest)
+        }
+        Err(e) => {
+            let restored = undo_swap(&journal);
+            remove_journal(journal_dir, flavor, addon);
+            match restored {
+                Ok(()) => Err(e),
+                Err(undo_error) => Err(format!("{} (rollback failed: {})", e, undo_error)),
+            }
+        }
+    }
+}
+
+fn swap_into_place(journal_dir: &Path, mut journal: SwapJournal) -> Result<(), String> {
+    if journal.dest.exists() {
+        if let Some(parent) = journal.aside.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create directory: {}", e))?;
+        }
+        fs::rename(&journal.dest, &journal.aside)
+            .map_err(|e| format!("Failed to move existing addon aside: {}", e))?;
+    }
+    journal.step = SwapStep::OldMovedAside;
+    write_journal(journal_dir, &journal)?;
+
+    fs::rename(&journal.staging, &journal.dest)
+        .map_err(|e| format!("Failed to move new addon into place: {}", e))?;
+    journal.step = SwapStep::Swapped;
+    write_journal(journal_dir, &journal)?;
+
+    Ok(())
+}
+
+/// Put the old folder back and drop the staged one
+fn undo_swap(journal: &SwapJournal) -> Result<(), String> {
+    if journal.aside.exists() {
+        remove_if_exists(&journal.dest)?;
+        fs::rename(&journal.aside, &journal.dest)
+            .map_err(|e| format!("Failed to restore previous addon: {}", e))?;
+    }
+    remove_if_exists(&journal.staging)
+}
+
+/// Finish or undo a swap interrupted by a crash or power loss.
+/// Decisions are based on what is on disk; `step` may lag one rename behind.
+fn recover(journal: &SwapJournal) -> Result<(), String> {
+    if journal.dest.exists() {
+        // Either the swap never started or it completed: AddOns is consistent,
+        // only leftovers need cleaning up
+        remove_if_exists(&journal.staging)?;
+        remove_if_exists(&journal.aside)
+    } else if journal.aside.exists() {
+        // Old folder moved aside but the new one never arrived: undo
+        undo_swap(journal)
+    } else if journal.staging.exists() {
+        // First install interrupted before the final rename: finish it
+        fs::rename(&journal.staging, &journal.dest)
+            .map_err(|e| format!("Failed to move new addon into place: {}", e))
+    } else {
+        Ok(())
+    }
+}
+
+/// Recover every swap left unfinished by a previous run
+pub fn recover_interrupted_swaps() {
+    recover_interrupted_swaps_in(&get_journal_dir());
+}
+
+fn recover_interrupted_swaps_in(journal_dir: &Path) {
+    let Ok(entries) = fs::read_dir(journal_dir) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().is_none_or(|ext| ext != "json") {
+            // Half-written journal from a crash during write_journal
+            let _ = fs::remove_file(&path);
+            continue;
+        }
+
+        let journal = fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str::<SwapJournal>(&content).ok());
+
+        match journal {
+            Some(journal) => {
+                if recover(&journal).is_ok() {
+                    let _ = fs::remove_file(&path);
+                }
+            }
+            None => {
+                let _ = fs::remove_file(&path);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ADDON: &str = "Nihui_uf";
+
+    /// A WoW Interface folder with AddOns, the journal directory and a
+    /// downloaded new version of the addon
+    struct Fixture {
+        _root: tempfile::TempDir,
+        journal_dir: PathBuf,
+        addons_path: PathBuf,
+        source: PathBuf,
+    }
+
+    fn fixture() -> Fixture {
+        let root = tempfile::tempdir().unwrap();
+        let addons_path = root.path().join("Interface").join("AddOns");
+        let source = root.path().join("download").join(ADDON);
+
+        fs::create_dir_all(addons_path.join(ADDON)).unwrap();
+        fs::write(addons_path.join(ADDON).join("old.lua"), "old").unwrap();
+        fs::create_dir_all(&source).unwrap();
+        fs::write(source.join("new.lua"), "new").unwrap();
+
+        Fixture {
+            journal_dir: root.path().join("swap_journal"),
+            addons_path,
+            source,
+            _root: root,
+        }
+    }
+
+    fn journal(fixture: &Fixture, step: SwapStep) -> SwapJournal {
+        let interface_path = fixture.addons_path.parent().unwrap();
+        SwapJournal {
+            addon: ADDON.to_string(),
+            flavor: GameFlavor::Retail,
+            dest: fixture.addons_path.join(ADDON),
+            staging: interface_path.join(STAGING_DIR).join(ADDON),
+            aside: interface_path.join(ASIDE_DIR).join(ADDON),
+            step,
+        }
+    }
+
+    fn journal_is_empty(fixture: &Fixture) -> bool {
+        fs::read_dir(&fixture.journal_dir).map_or(true, |mut entries| entries.next().is_none())
+    }
+
+    #[test]
+    fn replaces_the_addon_and_cleans_up() {
+        let fixture = fixture();
+
+        let dest = replace_addon_dir_in(
+            &fixture.journal_dir,
+            &fixture.source,
+            &fixture.addons_path,
+            GameFlavor::Retail,
+            ADDON,
+        )
+        .unwrap();
+
+        assert!(dest.join("new.lua").exists());
+        assert!(!dest.join("old.lua").exists());
+        let journal = journal(&fixture, SwapStep::Swapped);
+        assert!(!journal.staging.exists());
+        assert!(!journal.aside.exists());
+        assert!(journal_is_empty(&fixture));
+    }
+
+    #[test]
+    fn restores_the_old_addon_after_a_crash_mid_swap() {
+        let fixture = fixture();
+        let journal = journal(&fixture, SwapStep::OldMovedAside);
+
+        // Crash right after the old folder was moved aside
+        copy_dir_all(&fixture.source, &journal.staging).unwrap();
+        fs::create_dir_all(journal.aside.parent().unwrap()).unwrap();
+        fs::rename(&journal.dest, &journal.aside).unwrap();
+        write_journal(&fixture.journal_dir, &journal).unwrap();
+
+        recover_interrupted_swaps_in(&fixture.journal_dir);
+
+        assert!(journal.dest.join("old.lua").exists());
+        assert!(!journal.dest.join("new.lua").exists());
+        assert!(!journal.staging.exists());
+        assert!(!journal.aside.exists());
+        assert!(journal_is_empty(&fixture));
+    }
+
+    #[test]
+    fn keeps_the_new_addon_after_a_crash_once_it_is_in_place() {
+        let fixture = fixture();
+        // The journal lags one rename behind the disk
+        let journal = journal(&fixture, SwapStep::OldMovedAside);
+
+        // Crash right after the new folder was moved in
+        fs::create_dir_all(journal.aside.parent().unwrap()).unwrap();
+        fs::rename(&journal.dest, &journal.aside).unwrap();
+        copy_dir_all(&fixture.source, &journal.dest).unwrap();
+        write_journal(&fixture.journal_dir, &journal).unwrap();
+
+        recover_interrupted_swaps_in(&fixture.journal_dir);
+
+        assert!(journal.dest.join("new.lua").exists());
+        assert!(!journal.aside.exists());
+        assert!(journal_is_empty(&fixture));
+    }
+
+    #[test]
+    fn finishes_an_interrupted_first_install() {
+        let fixture = fixture();
+        let journal = journal(&fixture, SwapStep::OldMovedAside);
+
+        fs::remove_dir_all(&journal.dest).unwrap();
+        copy_dir_all(&fixture.source, &journal.staging).unwrap();
+        write_journal(&fixture.journal_dir, &journal).unwrap();
+
+        recover_interrupted_swaps_in(&fixture.journal_dir);
+
+        assert!(journal.dest.join("new.lua").exists());
+        assert!(!journal.staging.exists());
+        assert!(journal_is_empty(&fixture));
+    }
+}
@@ -0,0 +1,311 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// ===========================
+// TOC FILE PARSER
+// ===========================
+
+/// Metadata and file list of an addon .toc file
+#[derive(Debug, Serialize, Clone, Default, PartialEq)]
+pub struct TocFile {
+    pub interface: Vec<u32>, // Several when one TOC targets multiple game versions
+    pub title: Option<String>,
+    pub localized_titles: BTreeMap<String, String>, // Locale (e.g. "frFR") -> title
+    pub notes: Option<String>,
+    pub author: Option<String>,
+    pub version: Option<String>,
+    pub dependencies: Vec<String>, // Dependencies, RequiredDeps and other Dep* tags
+    pub optional_deps: Vec<String>,
+    pub load_on_demand: bool,
+    pub saved_variables: Vec<String>,
+    pub saved_variables_per_character: Vec<String>,
+    pub extra: BTreeMap<String, String>, // X-* tags, without the prefix
+    pub files: Vec<String>,
+}
+
+impl TocFile {
+    /// Parse TOC content. Unknown tags are ignored; the first occurrence of a tag wins.
+    pub fn parse(content: &str) -> TocFile {
+        let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+        let mut toc = TocFile::default();
+        let mut seen: Vec<String> = Vec::new();
+
+        for line in content.lines() {
+            let line = line.trim();
+
+            if let Some(tag_line) = line.strip_prefix("##") {
+                let Some((tag, value)) = tag_line.split_once(':') else {
+                    continue;
+                };
+                let tag = tag.trim();
+                let key = tag.to_ascii_lowercase();
+                if tag.is_empty() || seen.contains(&key) {
+                    continue;
+                }
+                seen.push(key.clone());
+                toc.apply_tag(tag, &key, strip_color_codes(value).trim());
+            } else if line.starts_with('#') || line.is_empty() {
+                // Comment or blank line
+                continue;
+            } else {
+                toc.files.push(line.to_string());
+            }
+        }
+
+        toc
+    }
+
+    fn apply_tag(&mut self, tag: &str, key: &str, value: &str) {
+        if value.is_empty() {
+            return;
+        }
+
+        match key {
+            "interface" => {
+                self.interface = split_list(value)
+                    .iter()
+                    .filter_map(|v| v.parse().ok())
+                    .collect();
+            }
+            "title" => self.title = Some(value.to_string()),
+            "notes" => self.notes = Some(value.to_string()),
+            "author" => self.author = Some(value.to_string()),
+            "version" => self.version = Some(value.to_string()),
+            "optionaldeps" => self.optional_deps.extend(split_list(value)),
+            "loadondemand" => self.load_on_demand = value == "1",
+            "savedvariables" => self.saved_variables = split_list(value),
+            "savedvariablespercharacter" => self.saved_variables_per_character = split_list(value),
+            _ if key.starts_with("title-") => {
+                self.localized_titles.insert(tag["title-".len()..].to_string(), value.to_string());
+            }
+            // The client treats every tag starting with "Dep" as required dependencies
+            _ if key.starts_with("dep") || key == "requireddeps" => {
+                for dep in split_list(value) {
+                    if !self.dependencies.contains(&dep) {
+                        self.dependencies.push(dep);
+                    }
+                }
+            }
+            _ if key.starts_with("x-") => {
+                self.extra.insert(tag[2..].to_string(), value.to_string());
+            }
+            _ => {}
+        }
+    }
+}
+
+fn split_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Remove UI escape sequences: |cAARRGGBB colors, |r resets,
+/// |T...|t textures and |A...|a atlases. `||` is a literal pipe.
+pub fn strip_color_codes(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '|' {
+            result.push(c);
+            continue;
+        }
+
+        match chars.peek().copied() {
+            Some('|') => {
+                chars.next();
+                result.push('|');
+            }
+            Some('c') | Some('C') => {
+                chars.next();
+                for _ in 0..8 {
+                    if chars.next_if(|c| c.is_ascii_hexdigit()).is_none() {
+                        break;
+                    }
+                }
+            }
+            Some('r') | Some('R') => {
+                chars.next();
+            }
+            Some(open @ ('T' | 'A')) => {
+                chars.next();
+                let close = open.to_ascii_lowercase();
+                while let Some(c) = chars.next() {
+                    if c == '|' && chars.next_if_eq(&close).is_some() {
+                        break;
+                    }
+                }
+            }
+            _ => result.push('|'),
+        }
+    }
+
+    result
+}
+
+/// Client families that load different TOC files
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[allow(dead_code)] // Cata, Wrath and TBC have no live client at the moment
+pub enum TocClient {
+    Mainline, // Retail and its test realms
+    Mists,
+    Cata,
+    Wrath,
+    Tbc,
+    Vanilla, // Classic Era
+}
+
+impl TocClient {
+    /// Suffixes the client tries, in order, before the plain `<addon>.toc`
+    fn suffixes(self) -> &'static [&'static str] {
+        match self {
+            TocClient::Mainline => &["_Mainline", "-Mainline"],
+            TocClient::Mists => &["_Mists", "_Classic", "-Classic"],
+            TocClient::Cata => &["_Cata", "-Cata", "_Classic", "-Classic"],
+            TocClient::Wrath => &["_Wrath", "-WOTLKC", "_Classic", "-Classic"],
+            TocClient::Tbc => &["_TBC", "-BCC", "_Classic", "-Classic"],
+            TocClient::Vanilla => &["_Vanilla", "-Vanilla", "_Classic", "-Classic"],
+        }
+    }
+}
+
+/// TOC file names the client looks for, most specific first
+pub fn toc_candidates(addon_name: &str, client: TocClient) -> Vec<String> {
+    client
+        .suffixes()
+        .iter()
+        .map(|suffix| format!("{}{}.toc", addon_name, suffix))
+        .chain(std::iter::once(format!("{}.toc", addon_name)))
+        .collect()
+}
+
+/// Name of the TOC file the client would load among `file_names`.
+/// Matching ignores case, as the game does on Windows.
+pub fn pick_toc<'a>(file_names: &[&'a str], addon_name: &str, client: TocClient) -> Option<&'a str> {
+    toc_candidates(addon_name, client).into_iter().find_map(|candidate| {
+        let candidate = candidate.to_lowercase();
+        file_names
+            .iter()
+            .copied()
+            .find(|name| name.to_lowercase() == candidate)
+    })
+}
+
+/// TOC file the client would load from `addon_dir`
+pub fn find_toc(addon_dir: &Path, addon_name: &str, client: TocClient) -> Option<PathBuf> {
+    let files: Vec<String> = fs::read_dir(addon_dir)
+        .ok()?
+        .flatten()
+        .filter(|entry| entry.path().is_file())
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .collect();
+    let names: Vec<&str> = files.iter().map(String::as_str).collect();
+
+    pick_toc(&names, addon_name, client).map(|name| addon_dir.join(name))
+}
+
+/// Read and parse a .toc file from disk
+pub fn read_toc(toc_path: &Path) -> Result<TocFile, String> {
+    if !toc_path.exists() {
+        return Err("TOC file not found".to_string());
+    }
+
+    let bytes = fs::read(toc_path)
+        .map_err(|e| format!("Failed to read TOC file: {}", e))?;
+
+    // Some TOC files are saved in a legacy encoding; keep what can be read
+    Ok(TocFile::parse(&String::from_utf8_lossy(&bytes)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "\u{feff}## Interface: 110002, 110005\r\n\
+## Title: |cff00ccffNihui|r UnitFrames\r\n\
+## Title-frFR: Nihui Cadres\r\n\
+## Notes: Unit frames\r\n\
+## Author: Nihilop\r\n\
+## Version: 1.4.2\r\n\
+## Dependencies: Nihui_Core\r\n\
+## RequiredDeps: Nihui_Lib, Nihui_Core\r\n\
+## OptionalDeps: Details, , WeakAuras\r\n\
+## LoadOnDemand: 0\r\n\
+## SavedVariables: NihuiDB\r\n\
+## SavedVariablesPerCharacter: NihuiCharDB, NihuiLayout\r\n\
+## X-Website: https://github.com/Nihilop\r\n\
+## X-Curse-Project-ID: 1234\r\n\
+# A comment\r\n\
+\r\n\
+Libs\\embeds.xml\r\n\
+core.lua\r\n";
+
+    #[test]
+    fn parses_every_field() {
+        let toc = TocFile::parse(SAMPLE);
+
+        assert_eq!(toc.interface, vec![110002, 110005]);
+        assert_eq!(toc.title.as_deref(), Some("Nihui UnitFrames"));
+        assert_eq!(toc.localized_titles.get("frFR").map(String::as_str), Some("Nihui Cadres"));
+        assert_eq!(toc.notes.as_deref(), Some("Unit frames"));
+        assert_eq!(toc.author.as_deref(), Some("Nihilop"));
+        assert_eq!(toc.version.as_deref(), Some("1.4.2"));
+        assert_eq!(toc.dependencies, vec!["Nihui_Core", "Nihui_Lib"]);
+        assert_eq!(toc.optional_deps, vec!["Details", "WeakAuras"]);
+        assert!(!toc.load_on_demand);
+        assert_eq!(toc.saved_variables, vec!["NihuiDB"]);
+        assert_eq!(toc.saved_variables_per_character, vec!["NihuiCharDB", "NihuiLayout"]);
+        assert_eq!(toc.extra.get("Website").map(String::as_str), Some("https://github.com/Nihilop"));
+        assert_eq!(toc.extra.get("Curse-Project-ID").map(String::as_str), Some("1234"));
+        assert_eq!(toc.files, vec!["Libs\\embeds.xml", "core.lua"]);
+    }
+
+    #[test]
+    fn first_tag_wins_and_tags_are_case_insensitive() {
+        let toc = TocFile::parse("## version: 1.0\n## Version: 2.0\n##LoadOnDemand:1\n");
+        assert_eq!(toc.version.as_deref(), Some("1.0"));
+        assert!(toc.load_on_demand);
+    }
+
+    #[test]
+    fn resolves_flavor_specific_toc_files() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["Nihui_uf.toc", "Nihui_uf_Vanilla.toc", "nihui_uf-classic.toc"] {
+            fs::write(dir.path().join(name), "").unwrap();
+        }
+
+        let file_name = |client| {
+            find_toc(dir.path(), "Nihui_uf", client)
+                .map(|path| path.file_name().unwrap().to_string_lossy().to_string())
+        };
+
+        assert_eq!(file_name(TocClient::Mainline).as_deref(), Some("Nihui_uf.toc"));
+        assert_eq!(file_name(TocClient::Vanilla).as_deref(), Some("Nihui_uf_Vanilla.toc"));
+        assert_eq!(file_name(TocClient::Cata).as_deref(), Some("nihui_uf-classic.toc"));
+        assert_eq!(find_toc(dir.path(), "Other", TocClient::Mainline), None);
+    }
+
+    #[test]
+    fn picks_the_toc_among_listed_names() {
+        let names = ["README.md", "nihui_uf-classic.toc", "Nihui_uf.toc", "Nihui_uf_Vanilla.toc"];
+
+        assert_eq!(pick_toc(&names, "Nihui_uf", TocClient::Mainline), Some("Nihui_uf.toc"));
+        assert_eq!(pick_toc(&names, "Nihui_uf", TocClient::Vanilla), Some("Nihui_uf_Vanilla.toc"));
+        assert_eq!(pick_toc(&names, "Nihui_uf", TocClient::Tbc), Some("nihui_uf-classic.toc"));
+        assert_eq!(pick_toc(&names, "Other", TocClient::Mainline), None);
+    }
+
+    #[test]
+    fn strips_escape_sequences() {
+        assert_eq!(strip_color_codes("|cFFFF0000Red|r text"), "Red text");
+        assert_eq!(strip_color_codes("|TInterface\\Icons\\foo:16|t Icon"), " Icon");
+        assert_eq!(strip_color_codes("|A:atlas:16:16|a Atlas"), " Atlas");
+        assert_eq!(strip_color_codes("a || b"), "a | b");
+    }
+}
@@ -0,0 +1,605 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+use crate::build_info;
+use crate::flavor::GameFlavor;
+use crate::graphql;
+use crate::http::{HttpClient, SharedHttpClient};
+use crate::install_record::{self, InstallRecord};
+use crate::toc;
+use crate::version::{compare_versions, VersionComparison};
+use crate::{load_config, read_addon_version, resolve_addon_list, AddonDefinition, UpdateMode};
+
+// ===========================
+// UPDATE CHECK
+// ===========================
+
+/// Default number of addons checked at the same time
+pub const DEFAULT_CHECK_CONCURRENCY: usize = 4;
+
+/// Result of comparing an installed addon with its GitHub source
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UpdateCheck {
+    pub local_name: String,
+    pub flavor: GameFlavor,
+    pub installed: bool,
+    pub local_version: Option<String>,
+    pub remote_version: Option<String>,
+    pub installed_commit: Option<String>,
+    pub remote_commit: Option<String>,
+    pub update_available: bool,
+    pub new_commits: bool, // Remote head moved since install, even without a version bump
+    pub comparison: Option<VersionComparison>, // Remote version relative to the local one
+    pub interface_outdated: bool, // TOC Interface older than the installed client
+}
+
+/// Whether the installed copy of an addon targets an older Interface than the
+/// client. False when either is unknown.
+pub fn is_interface_outdated(
+    addon_path: &Path,
+    addon_name: &str,
+    flavor: GameFlavor,
+    client_interface: Option<u32>,
+) -> bool {
+    let Some(client_interface) = client_interface else {
+        return false;
+    };
+    toc::find_toc(addon_path, addon_name, flavor.toc_client())
+        .and_then(|toc_path| toc::read_toc(&toc_path).ok())
+        .is_some_and(|toc| build_info::is_interface_outdated(&toc.interface, client_interface))
+}
+
+/// Whether installing the remote version is an update, given how it compares
+fn is_update(comparison: VersionComparison, local: &str, remote: &str) -> bool {
+    match comparison {
+        VersionComparison::Newer => true,
+        VersionComparison::Older | VersionComparison::Equal => false,
+        // Unknown ordering: any difference is offered as an update
+        VersionComparison::Incomparable => local.trim() != remote.trim(),
+    }
+}
+
+/// Whether the install record was made from the branch the addon now tracks
+fn record_matches_branch(record: &InstallRecord, addon_def: &AddonDefinition) -> bool {
+    record.update_mode == UpdateMode::Branch
+        && record.branch == addon_def.branch
+        && record.commit_sha.is_some()
+}
+
+/// What the remote source of an addon points to
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RemoteState {
+    pub release_tag: Option<String>, // Latest release, release mode only
+    pub head_commit: Option<String>, // Head of the tracked branch, branch mode only
+    pub toc_version: Option<String>, // Version in the TOC at the branch head, branch mode only
+}
+
+/// The installed side of a check, before anything is fetched
+fn local_state(wow_path: &str, flavor: GameFlavor, addon_def: &AddonDefinition) -> (UpdateCheck, Option<InstallRecord>) {
+    let addon_path = flavor.addons_path(wow_path).join(&addon_def.local_name);
+
+    let installed = addon_path.exists();
+    let local_version = if installed {
+        read_addon_version(&addon_path, &addon_def.local_name, flavor).ok()
+    } else {
+        None
+    };
+    let record = if installed {
+        install_record::load_record(flavor, &addon_def.local_name)
+    } else {
+        None
+    };
+
+    let check = UpdateCheck {
+        local_name: addon_def.local_name.to_string(),
+        flavor,
+        installed,
+        local_version,
+        remote_version: None,
+        installed_commit: record.as_ref().and_then(|r| r.commit_sha.clone()),
+        remote_commit: None,
+        update_available: false,
+        new_commits: false,
+        comparison: None,
+        interface_outdated: installed
+            && is_interface_outdated(
+                &addon_path,
+                &addon_def.local_name,
+                flavor,
+                build_info::installed_interface(wow_path, flavor),
+            ),
+    };
+
+    (check, record)
+}
+
+/// Fetch the remote state of an addon with REST calls. The branch head is
+/// only resolved when `need_commit`, i.e. when a record can be compared to it.
+async fn fetch_remote_state(
+    http: &HttpClient,
+    flavor: GameFlavor,
+    addon_def: &AddonDefinition,
+    need_commit: bool,
+) -> Result<RemoteState, String> {
+    match addon_def.update_mode {
+        UpdateMode::Release => {
+            let release = crate::github_release(http, &addon_def.github_owner, &addon_def.github_repo).await?;
+            Ok(RemoteState {
+                release_tag: Some(release.tag_name),
+                ..RemoteState::default()
+            })
+        }
+        UpdateMode::Branch => {
+            let branch = addon_def
+                .branch
+                .as_deref()
+                .ok_or("Branch name is required for branch mode")?;
+
+            let toc_version = crate::github_toc_version(
+                http,
+                &addon_def.github_owner,
+                &addon_def.github_repo,
+                branch,
+                &addon_def.local_name,
+                flavor,
+            )
+            .await
+            .ok();
+
+            let head_commit = if need_commit {
+                Some(crate::fetch_commit_sha(http, &addon_def.github_owner, &addon_def.github_repo, branch).await?)
+            } else {
+                None
+            };
+
+            Ok(RemoteState {
+                head_commit,
+                toc_version,
+                ..RemoteState::default()
+            })
+        }
+    }
+}
+
+/// Fill in the remote side of a check and decide whether it is an update
+pub fn compare_with_remote(
+    mut check: UpdateCheck,
+    record: Option<&InstallRecord>,
+    addon_def: &AddonDefinition,
+    remote: RemoteState,
+) -> Result<UpdateCheck, String> {
+    match addon_def.update_mode {
+        UpdateMode::Release => {
+            let tag_name = remote.release_tag.ok_or("No release found")?;
+            let remote_version = tag_name.trim_start_matches('v').to_string();
+
+            if check.installed {
+                // Prefer the tag recorded at install time over the TOC version
+                let installed_version = match record.and_then(|r| r.tag.as_ref()) {
+                    Some(installed_tag) => installed_tag.clone(),
+                    None => check
+                        .local_version
+                        .clone()
+                        .ok_or("Version not found in .toc file")?,
+                };
+                let comparison = compare_versions(&installed_version, &tag_name);
+                check.update_available = is_update(
+                    comparison,
+                    installed_version.trim_start_matches('v'),
+                    &remote_version,
+                );
+                check.comparison = Some(comparison);
+            }
+            check.remote_version = Some(remote_version);
+        }
+        UpdateMode::Branch => {
+            let remote_version = remote.toc_version;
+
+            match record.filter(|r| record_matches_branch(r, addon_def)) {
+                Some(record) => {
+                    let remote_commit = remote.head_commit.ok_or("Failed to get commit SHA")?;
+
+                    check.new_commits = record.commit_sha.as_deref() != Some(remote_commit.as_str());
+                    check.update_available = check.new_commits;
+                    check.remote_commit = Some(remote_commit);
+                    check.comparison = check
+                        .local_version
+                        .as_ref()
+                        .zip(remote_version.as_ref())
+                        .map(|(local, remote)| compare_versions(local, remote));
+                }
+                None if check.installed => {
+                    // No usable record: fall back to the TOC version
+                    let local_version = check
+                        .local_version
+                        .as_ref()
+                        .ok_or("Version not found in .toc file")?;
+                    let remote_version = remote_version
+                        .as_ref()
+                        .ok_or("Version not found in remote TOC file")?;
+                    let comparison = compare_versions(local_version, remote_version);
+                    check.update_available = is_update(comparison, local_version, remote_version);
+                    check.comparison = Some(comparison);
+                }
+                None => {}
+            }
+            check.remote_version = remote_version;
+        }
+    }
+
+    Ok(check)
+}
+
+/// Compare the installed copy of an addon with its remote source
+pub async fn check_update(
+    http: &HttpClient,
+    wow_path: &str,
+    flavor: GameFlavor,
+    addon_def: &AddonDefinition,
+) -> Result<UpdateCheck, String> {
+    let (check, record) = local_state(wow_path, flavor, addon_def);
+    check_against_remote(http, flavor, addon_def, check, record).await
+}
+
+/// Fetch the remote state of an addon and compare it with the local one
+async fn check_against_remote(
+    http: &HttpClient,
+    flavor: GameFlavor,
+    addon_def: &AddonDefinition,
+    check: UpdateCheck,
+    record: Option<InstallRecord>,
+) -> Result<UpdateCheck, String> {
+    let need_commit = record.as_ref().is_some_and(|r| record_matches_branch(r, addon_def));
+    let remote = fetch_remote_state(http, flavor, addon_def, need_commit).await?;
+
+    compare_with_remote(check, record.as_ref(), addon_def, remote)
+}
+
+/// Check a single addon for updates
+#[tauri::command]
+pub async fn check_addon_update(
+    wow_path: String,
+    flavor: GameFlavor,
+    addon_def: AddonDefinition,
+    http: tauri::State<'_, SharedHttpClient>,
+) -> Result<AddonCheckResult, String> {
+    let local_name = addon_def.local_name.clone();
+    check_all(&http.client(), &wow_path, flavor, vec![addon_def], 1)
+        .await
+        .pop()
+        .ok_or_else(|| format!("Update check of {} was aborted", local_name))
+}
+
+/// Update status of one addon
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "state", rename_all = "kebab-case")]
+pub enum AddonStatus {
+    Checking,
+    NotInstalled {
+        remote_version: Option<String>,
+    },
+    UpToDate {
+        local_version: Option<String>,
+        remote_version: Option<String>,
+    },
+    UpdateAvailable {
+        local_version: Option<String>,
+        remote_version: Option<String>,
+        new_commits: bool,
+        downgrade: bool, // New commits, but the remote version is lower
+    },
+    LocalAhead {
+        local_version: Option<String>,
+        remote_version: Option<String>,
+    },
+    Error {
+        local_version: Option<String>,
+        message: String,
+    },
+    RateLimited {
+        local_version: Option<String>,
+        reset_at: u64, // Unix timestamp (seconds) when checks can resume
+    },
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AddonCheckResult {
+    pub local_name: String,
+    pub flavor: GameFlavor,
+    pub status: AddonStatus,
+    pub interface_outdated: bool, // TOC Interface older than the installed client
+}
+
+/// Turn the outcome of a check into a status. `local_version` is the one
+/// read before the check, still reported when it fails.
+fn status_of(http: &HttpClient, local_version: Option<String>, outcome: Result<UpdateCheck, String>) -> AddonStatus {
+    match outcome {
+        Ok(check) if !check.installed => AddonStatus::NotInstalled {
+            remote_version: check.remote_version,
+        },
+        Ok(check) if check.update_available => AddonStatus::UpdateAvailable {
+            local_version: check.local_version,
+            remote_version: check.remote_version,
+            new_commits: check.new_commits,
+            downgrade: check.comparison == Some(VersionComparison::Older),
+        },
+        Ok(check) if check.comparison == Some(VersionComparison::Older) => AddonStatus::LocalAhead {
+            local_version: check.local_version,
+            remote_version: check.remote_version,
+        },
+        Ok(check) => AddonStatus::UpToDate {
+            local_version: check.local_version,
+            remote_version: check.remote_version,
+        },
+        Err(message) => match http.rate_limit().filter(|r| r.is_exhausted(install_record::now_timestamp())) {
+            Some(rate_limit) => AddonStatus::RateLimited {
+                local_version,
+                reset_at: rate_limit.reset_at,
+            },
+            None => AddonStatus::Error { local_version, message },
+        },
+    }
+}
+
+/// Check one addon and turn the outcome into a status
+pub async fn check_status(
+    http: &HttpClient,
+    wow_path: &str,
+    flavor: GameFlavor,
+    addon_def: &AddonDefinition,
+) -> AddonStatus {
+    let (check, record) = local_state(wow_path, flavor, addon_def);
+    let local_version = check.local_version.clone();
+    let outcome = check_against_remote(http, flavor, addon_def, check, record).await;
+    status_of(http, local_version, outcome)
+}
+
+/// Check every addon with a single GraphQL query. Fails as a whole when the
+/// query does, so the caller can fall back to REST.
+async fn check_all_graphql(
+    http: &HttpClient,
+    wow_path: &str,
+    flavor: GameFlavor,
+    addons: &[AddonDefinition],
+) -> Result<Vec<Option<AddonStatus>>, String> {
+    let remotes = graphql::fetch_remote_states(http, flavor, addons).await?;
+
+    Ok(addons
+        .iter()
+        .zip(remotes)
+        .map(|(addon_def, remote)| {
+            let (check, record) = local_state(wow_path, flavor, addon_def);
+            let local_version = check.local_version.clone();
+            let outcome = remote.and_then(|remote| compare_with_remote(check, record.as_ref(), addon_def, remote));
+            Some(status_of(http, local_version, outcome))
+        })
+        .collect())
+}
+
+/// Check every addon with REST calls, at most `concurrency` addons at a time
+async fn check_all_rest(
+    http: &HttpClient,
+    wow_path: &str,
+    flavor: GameFlavor,
+    addons: &[AddonDefinition],
+    concurrency: usize,
+) -> Vec<Option<AddonStatus>> {
+    let semaphore = Arc::new(Semaphore::new(concurrency.max(1)));
+    let mut tasks = JoinSet::new();
+
+    for (index, addon_def) in addons.iter().cloned().enumerate() {
+        let semaphore = semaphore.clone();
+        let http = http.clone();
+        let wow_path = wow_path.to_string();
+        tasks.spawn(async move {
+            let _permit = semaphore.acquire_owned().await;
+            (index, check_status(&http, &wow_path, flavor, &addon_def).await)
+        });
+    }
+
+    let mut statuses: Vec<Option<AddonStatus>> = vec![None; addons.len()];
+    while let Some(joined) = tasks.join_next().await {
+        if let Ok((index, status)) = joined {
+            statuses[index] = Some(status);
+        }
+    }
+    statuses
+}
+
+/// Check every addon: in one GraphQL query when a GitHub token is set,
+/// otherwise (or if the query fails) with concurrent REST calls.
+/// Results keep the order of `addons`.
+pub async fn check_all(
+    http: &HttpClient,
+    wow_path: &str,
+    flavor: GameFlavor,
+    addons: Vec<AddonDefinition>,
+    concurrency: usize,
+) -> Vec<AddonCheckResult> {
+    let client_interface = build_info::installed_interface(wow_path, flavor);
+    let addons_path = flavor.addons_path(wow_path);
+
+    let batched = if http.has_token() {
+        check_all_graphql(http, wow_path, flavor, &addons).await.ok()
+    } else {
+        None
+    };
+    let statuses = match batched {
+        Some(statuses) => statuses,
+        None => check_all_rest(http, wow_path, flavor, &addons, concurrency).await,
+    };
+
+    addons
+        .into_iter()
+        .zip(statuses)
+        .map(|(addon_def, status)| AddonCheckResult {
+            interface_outdated: is_interface_outdated(
+                &addons_path.join(&addon_def.local_name),
+                &addon_def.local_name,
+                flavor,
+                client_interface,
+            ),
+            local_name: addon_def.local_name.into(),
+            flavor,
+            status: status.unwrap_or_else(|| AddonStatus::Error {
+                local_version: None,
+                message: "Update check was aborted".to_string(),
+            }),
+        })
+        .collect()
+}
+
+/// Check every addon of the addon list for updates
+#[tauri::command]
+pub async fn check_all_updates(
+    wow_path: String,
+    flavor: GameFlavor,
+    http: tauri::State<'_, SharedHttpClient>,
+) -> Result<Vec<AddonCheckResult>, String> {
+    let config = load_config().unwrap_or_default();
+    let addons = resolve_addon_list(&config, flavor);
+
+    Ok(check_all(&http.client(), &wow_path, flavor, addons, config.check_concurrency).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::addon_name::AddonFolderName;
+
+    fn addon(update_mode: UpdateMode) -> AddonDefinition {
+        let branch = (update_mode == UpdateMode::Branch).then(|| "main".to_string());
+        AddonDefinition {
+            local_name: AddonFolderName::from_static("Nihui_uf"),
+            nice_name: "Nihui UnitFrames".to_string(),
+            github_owner: "nihui".to_string(),
+            github_repo: "Nihui_uf".to_string(),
+            description: String::new(),
+            update_mode,
+            branch,
+        }
+    }
+
+    fn installed(local_version: &str) -> UpdateCheck {
+        UpdateCheck {
+            local_name: "Nihui_uf".to_string(),
+            flavor: GameFlavor::Retail,
+            installed: true,
+            local_version: Some(local_version.to_string()),
+            remote_version: None,
+            installed_commit: None,
+            remote_commit: None,
+            update_available: false,
+            new_commits: false,
+            comparison: None,
+            interface_outdated: false,
+        }
+    }
+
+    fn record(addon_def: &AddonDefinition, commit_sha: Option<&str>, tag: Option<&str>) -> InstallRecord {
+        InstallRecord {
+            local_name: addon_def.local_name.clone(),
+            flavor: GameFlavor::Retail,
+            source_url: String::new(),
+            commit_sha: commit_sha.map(str::to_string),
+            tag: tag.map(str::to_string),
+            update_mode: addon_def.update_mode.clone(),
+            branch: addon_def.branch.clone(),
+            installed_at: 0,
+            files: Vec::new(),
+        }
+    }
+
+    fn branch_head(commit: &str, toc_version: &str) -> RemoteState {
+        RemoteState {
+            head_commit: Some(commit.to_string()),
+            toc_version: Some(toc_version.to_string()),
+            ..RemoteState::default()
+        }
+    }
+
+    fn release(tag: &str) -> RemoteState {
+        RemoteState {
+            release_tag: Some(tag.to_string()),
+            ..RemoteState::default()
+        }
+    }
+
+    #[test]
+    fn new_commits_are_an_update_even_with_the_same_toc_version() {
+        let addon_def = addon(UpdateMode::Branch);
+        let record = record(&addon_def, Some("aaa111"), None);
+
+        let check = compare_with_remote(installed("1.4.0"), Some(&record), &addon_def, branch_head("bbb222", "1.4.0")).unwrap();
+        assert!(check.update_available);
+        assert!(check.new_commits);
+        assert_eq!(check.comparison, Some(VersionComparison::Equal));
+        assert_eq!(check.remote_commit.as_deref(), Some("bbb222"));
+
+        let check = compare_with_remote(installed("1.4.0"), Some(&record), &addon_def, branch_head("aaa111", "1.4.0")).unwrap();
+        assert!(!check.update_available);
+        assert!(!check.new_commits);
+    }
+
+    #[test]
+    fn without_a_recorded_commit_the_toc_version_decides() {
+        let addon_def = addon(UpdateMode::Branch);
+        let record = record(&addon_def, None, None);
+        // No head was fetched, since there is no commit to compare it to
+        let remote = |version: &str| RemoteState {
+            toc_version: Some(version.to_string()),
+            ..RemoteState::default()
+        };
+
+        let check = compare_with_remote(installed("1.4.0"), Some(&record), &addon_def, remote("1.4.0")).unwrap();
+        assert!(!check.update_available);
+        assert!(!check.new_commits);
+
+        let check = compare_with_remote(installed("1.4.0"), None, &addon_def, remote("1.5.0")).unwrap();
+        assert!(check.update_available);
+        assert_eq!(check.comparison, Some(VersionComparison::Newer));
+    }
+
+    #[test]
+    fn a_newer_release_tag_is_an_update() {
+        let addon_def = addon(UpdateMode::Release);
+        let record = record(&addon_def, Some("aaa111"), Some("v1.4.0"));
+
+        let check = compare_with_remote(installed("1.4.0"), Some(&record), &addon_def, release("v1.5.0")).unwrap();
+
+        assert!(check.update_available);
+        assert_eq!(check.comparison, Some(VersionComparison::Newer));
+        assert_eq!(check.remote_version.as_deref(), Some("1.5.0"));
+    }
+
+    #[test]
+    fn an_older_release_tag_is_a_downgrade_not_an_update() {
+        let addon_def = addon(UpdateMode::Release);
+        let record = record(&addon_def, Some("aaa111"), Some("v1.5.0"));
+        let http = HttpClient::new(&crate::AppConfig::default(), None).unwrap();
+
+        let check = compare_with_remote(installed("1.5.0"), Some(&record), &addon_def, release("v1.4.0")).unwrap();
+
+        assert!(!check.update_available);
+        assert_eq!(check.comparison, Some(VersionComparison::Older));
+        assert!(matches!(status_of(&http, None, Ok(check)), AddonStatus::LocalAhead { .. }));
+    }
+
+    #[test]
+    fn failed_checks_keep_the_local_version() {
+        let http = HttpClient::new(&crate::AppConfig::default(), None).unwrap();
+
+        let status = status_of(&http, Some("1.2.0".to_string()), Err("GitHub API error".to_string()));
+
+        match status {
+            AddonStatus::Error { local_version, message } => {
+                assert_eq!(local_version.as_deref(), Some("1.2.0"));
+                assert_eq!(message, "GitHub API error");
+            }
+            other => panic!("unexpected status {:?}", other),
+        }
+    }
+}
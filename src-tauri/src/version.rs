@@ -0,0 +1,297 @@
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+// ===========================
+// VERSION PARSING
+// ===========================
+
+/// How a version string was understood
+#[derive(Debug, Clone, PartialEq)]
+pub enum VersionKind {
+    Semver,      // 1.2.3, 1.2.3-beta.1+build
+    Loose,       // 1.2, 1.2.3b, 11.0.2.5
+    Date,        // 20240131, 2024-01-31, 2024.01.31
+    Placeholder, // @project-version@, unknown, empty
+}
+
+#[derive(Debug, Clone)]
+pub struct Version {
+    pub kind: VersionKind,
+    numbers: Vec<u64>,
+    letter: Option<String>,    // Trailing letters on the last number: 1.2.3b
+    pre: Vec<PreIdentifier>,   // Pre-release identifiers: 1.2.3-beta.1
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PreIdentifier {
+    Numeric(u64),
+    Alpha(String),
+}
+
+impl PartialOrd for PreIdentifier {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PreIdentifier {
+    // Semver rule: numeric identifiers sort before alphanumeric ones
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (PreIdentifier::Numeric(a), PreIdentifier::Numeric(b)) => a.cmp(b),
+            (PreIdentifier::Numeric(_), PreIdentifier::Alpha(_)) => Ordering::Less,
+            (PreIdentifier::Alpha(_), PreIdentifier::Numeric(_)) => Ordering::Greater,
+            (PreIdentifier::Alpha(a), PreIdentifier::Alpha(b)) => a.cmp(b),
+        }
+    }
+}
+
+/// Result of comparing a remote version with the local one
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum VersionComparison {
+    Newer,        // Remote is newer than local
+    Older,        // Remote is older than local (installing it is a downgrade)
+    Equal,
+    Incomparable, // Placeholder or formats that cannot be ordered
+}
+
+fn placeholder() -> Version {
+    Version {
+        kind: VersionKind::Placeholder,
+        numbers: Vec::new(),
+        letter: None,
+        pre: Vec::new(),
+    }
+}
+
+fn parse_pre(pre: &str) -> Vec<PreIdentifier> {
+    pre.split('.')
+        .filter(|part| !part.is_empty())
+        .map(|part| match part.parse::<u64>() {
+            Ok(n) => PreIdentifier::Numeric(n),
+            Err(_) => PreIdentifier::Alpha(part.to_ascii_lowercase()),
+        })
+        .collect()
+}
+
+/// Parse `YYYYMMDD`, `YYYY-MM-DD` or `YYYY.MM.DD` (optionally followed by more numbers)
+fn parse_date(core: &str) -> Option<Vec<u64>> {
+    // Checked on bytes: slicing at 8 could split a multi-byte character
+    let leading_digits = core
+        .as_bytes()
+        .get(..8)
+        .is_some_and(|bytes| bytes.iter().all(u8::is_ascii_digit));
+    let parts: Vec<u64> = if leading_digits && (core.len() == 8 || core[8..].starts_with('.')) {
+        let mut parts = vec![
+            core[0..4].parse().ok()?,
+            core[4..6].parse().ok()?,
+            core[6..8].parse().ok()?,
+        ];
+        for rest in core[8..].split('.').filter(|p| !p.is_empty()) {
+            parts.push(rest.parse().ok()?);
+        }
+        parts
+    } else {
+        let parts: Vec<&str> = core.split(['-', '.']).collect();
+        if parts.len() < 3 || parts[0].len() != 4 {
+            return None;
+        }
+        parts
+            .iter()
+            .map(|p| p.parse::<u64>().ok())
+            .collect::<Option<Vec<_>>>()?
+    };
+
+    let (year, month, day) = (parts[0], parts[1], parts[2]);
+    if (2000..=2999).contains(&year) && (1..=12).contains(&month) && (1..=31).contains(&day) {
+        Some(parts)
+    } else {
+        None
+    }
+}
+
+/// Parse a version string as found in TOC files and release tags
+pub fn parse_version(raw: &str) -> Version {
+    let trimmed = raw.trim();
+    let trimmed = trimmed
+        .strip_prefix('v')
+        .or_else(|| trimmed.strip_prefix('V'))
+        .unwrap_or(trimmed);
+
+    // Packager placeholders (@project-version@) and unknown versions
+    if trimmed.is_empty()
+        || trimmed.eq_ignore_ascii_case("unknown")
+        || (trimmed.starts_with('@') && trimmed.ends_with('@'))
+    {
+        return placeholder();
+    }
+
+    // Build metadata never affects ordering
+    let without_build = trimmed.split('+').next().unwrap_or(trimmed);
+
+    if let Some(numbers) = parse_date(without_build) {
+        return Version {
+            kind: VersionKind::Date,
+            numbers,
+            letter: None,
+            pre: Vec::new(),
+        };
+    }
+
+    let (core, pre) = match without_build.split_once('-') {
+        Some((core, pre)) => (core, parse_pre(pre)),
+        None => (without_build, Vec::new()),
+    };
+
+    let parts: Vec<&str> = core.split('.').collect();
+    let mut numbers = Vec::new();
+    let mut letter = None;
+
+    for (index, part) in parts.iter().enumerate() {
+        let digits_end = part.find(|c: char| !c.is_ascii_digit()).unwrap_or(part.len());
+        if digits_end == 0 {
+            return placeholder();
+        }
+        let number = match part[..digits_end].parse::<u64>() {
+            Ok(n) => n,
+            Err(_) => return placeholder(),
+        };
+        numbers.push(number);
+
+        let suffix = &part[digits_end..];
+        if !suffix.is_empty() {
+            // Letters are only allowed on the last component (1.2.3b)
+            if index != parts.len() - 1 || !suffix.chars().all(|c| c.is_ascii_alphabetic()) {
+                return placeholder();
+            }
+            letter = Some(suffix.to_ascii_lowercase());
+        }
+    }
+
+    let kind = if numbers.len() == 3 && letter.is_none() {
+        VersionKind::Semver
+    } else {
+        VersionKind::Loose
+    };
+
+    Version {
+        kind,
+        numbers,
+        letter,
+        pre,
+    }
+}
+
+/// Order two parsed versions, or `None` when they cannot be compared
+fn compare_parsed(a: &Version, b: &Version) -> Option<Ordering> {
+    if a.kind == VersionKind::Placeholder || b.kind == VersionKind::Placeholder {
+        return None;
+    }
+    // A date-based scheme cannot be ordered against a numbered one
+    if (a.kind == VersionKind::Date) != (b.kind == VersionKind::Date) {
+        return None;
+    }
+
+    let len = a.numbers.len().max(b.numbers.len());
+    for i in 0..len {
+        let x = a.numbers.get(i).copied().unwrap_or(0);
+        let y = b.numbers.get(i).copied().unwrap_or(0);
+        match x.cmp(&y) {
+            Ordering::Equal => continue,
+            other => return Some(other),
+        }
+    }
+
+    // 1.2.3 < 1.2.3a < 1.2.3b
+    match a.letter.cmp(&b.letter) {
+        Ordering::Equal => {}
+        other => return Some(other),
+    }
+
+    // 1.2.3-beta < 1.2.3
+    Some(match (a.pre.is_empty(), b.pre.is_empty()) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Greater,
+        (false, true) => Ordering::Less,
+        (false, false) => a.pre.cmp(&b.pre),
+    })
+}
+
+/// Compare a remote version against the installed one
+pub fn compare_versions(local: &str, remote: &str) -> VersionComparison {
+    match compare_parsed(&parse_version(remote), &parse_version(local)) {
+        Some(Ordering::Greater) => VersionComparison::Newer,
+        Some(Ordering::Less) => VersionComparison::Older,
+        Some(Ordering::Equal) => VersionComparison::Equal,
+        None if local.trim() == remote.trim() => VersionComparison::Equal,
+        None => VersionComparison::Incomparable,
+    }
+}
+
+/// Whether `version` is at least `required`
+pub fn is_at_least(version: &str, required: &str) -> bool {
+    matches!(
+        compare_versions(required, version),
+        VersionComparison::Newer | VersionComparison::Equal
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn orders_semver_with_pre_releases() {
+        assert_eq!(compare_versions("1.2.3", "1.2.4"), VersionComparison::Newer);
+        assert_eq!(compare_versions("1.2.3-beta.1", "1.2.3"), VersionComparison::Newer);
+        assert_eq!(compare_versions("1.2.3-alpha", "1.2.3-beta"), VersionComparison::Newer);
+        assert_eq!(compare_versions("1.2.3-beta.2", "1.2.3-beta.11"), VersionComparison::Newer);
+        assert_eq!(compare_versions("1.2.3-beta.1", "1.2.3-beta"), VersionComparison::Older);
+        assert_eq!(compare_versions("1.2.3+build.1", "1.2.3+build.2"), VersionComparison::Equal);
+        assert_eq!(parse_version("1.2.3-rc.1").kind, VersionKind::Semver);
+    }
+
+    #[test]
+    fn compares_loose_versions() {
+        assert_eq!(compare_versions("v1.2", "1.2.0"), VersionComparison::Equal);
+        assert_eq!(compare_versions("1.2.3", "1.2.3b"), VersionComparison::Newer);
+        assert_eq!(compare_versions("11.0.2.5", "11.0.2.10"), VersionComparison::Newer);
+        assert_eq!(parse_version("v1.2").kind, VersionKind::Loose);
+    }
+
+    #[test]
+    fn parses_dates_with_every_separator() {
+        for raw in ["20240131", "2024-01-31", "2024.01.31"] {
+            assert_eq!(parse_version(raw).kind, VersionKind::Date, "{}", raw);
+        }
+        assert_eq!(compare_versions("2024-01-31", "20240201"), VersionComparison::Newer);
+        assert_eq!(compare_versions("2024.01.31", "2024-01-31"), VersionComparison::Equal);
+        assert_eq!(compare_versions("20240131.2", "20240131.1"), VersionComparison::Older);
+        assert_eq!(compare_versions("20240131", "1.2.3"), VersionComparison::Incomparable);
+    }
+
+    #[test]
+    fn falls_back_on_placeholders_and_garbage() {
+        assert_eq!(parse_version("@project-version@").kind, VersionKind::Placeholder);
+        assert_eq!(parse_version("").kind, VersionKind::Placeholder);
+        assert_eq!(parse_version("release-candidate").kind, VersionKind::Placeholder);
+        assert_eq!(compare_versions("@project-version@", "1.0.0"), VersionComparison::Incomparable);
+        assert_eq!(compare_versions("unknown", "unknown"), VersionComparison::Equal);
+        assert_eq!(compare_versions("nightly", "latest"), VersionComparison::Incomparable);
+    }
+
+    #[test]
+    fn detects_downgrades() {
+        assert_eq!(compare_versions("2.0.0", "1.9.9"), VersionComparison::Older);
+        assert!(!is_at_least("1.9.9", "2.0.0"));
+        assert!(is_at_least("2.0.0", "2.0.0"));
+    }
+
+    #[test]
+    fn multi_byte_characters_do_not_panic() {
+        assert_eq!(parse_version("1234567é").kind, VersionKind::Placeholder);
+        assert_eq!(parse_version("2024013é").kind, VersionKind::Placeholder);
+        assert_eq!(compare_versions("1.0.0", "v1.0.0-béta"), VersionComparison::Older);
+    }
+}
@@ -0,0 +1,196 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::flavor;
+use crate::product_db;
+
+// ===========================
+// WINE PREFIX SCANNER
+// ===========================
+
+/// Folders holding one Wine prefix per subfolder, relative to the home directory
+const PREFIX_PARENTS: &[&str] = &[
+    "Games",                                                // Lutris
+    ".local/share/bottles/bottles",                         // Bottles
+    ".var/app/com.usebottles.bottles/data/bottles/bottles", // Bottles (Flatpak)
+];
+
+/// Steam libraries whose compatdata/<app id>/pfx folders are Proton prefixes
+const STEAM_LIBRARIES: &[&str] = &[
+    ".steam/steam/steamapps",
+    ".local/share/Steam/steamapps",
+    ".var/app/com.valvesoftware.Steam/.local/share/Steam/steamapps",
+];
+
+fn is_prefix(path: &Path) -> bool {
+    path.join("drive_c").is_dir()
+}
+
+fn sub_dirs(path: &Path) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| path.is_dir())
+                .collect()
+        })
+        .unwrap_or_default();
+    dirs.sort();
+    dirs
+}
+
+/// Wine prefixes found in the usual places under a home directory.
+/// Folders reachable through several paths (e.g. ~/.steam/steam) are listed once.
+pub fn find_prefixes(home: &Path) -> Vec<PathBuf> {
+    let mut candidates = vec![home.join(".wine")];
+    for parent in PREFIX_PARENTS {
+        candidates.extend(sub_dirs(&home.join(parent)));
+    }
+    for library in STEAM_LIBRARIES {
+        candidates.extend(
+            sub_dirs(&home.join(library).join("compatdata"))
+                .into_iter()
+                .map(|app| app.join("pfx")),
+        );
+    }
+
+    let mut seen = HashSet::new();
+    candidates
+        .into_iter()
+        .filter(|path| is_prefix(path))
+        .filter(|path| seen.insert(fs::canonicalize(path).unwrap_or_else(|_| path.clone())))
+        .collect()
+}
+
+/// Map a Windows path of a prefix ("C:/Games/World of Warcraft") to the Linux
+/// folder behind it. Drive C is drive_c, other drives go through dosdevices.
+pub fn prefix_path(prefix: &Path, windows_path: &str) -> Option<PathBuf> {
+    let normalized = windows_path.replace('\\', "/");
+    let (drive, rest) = normalized.split_once(':')?;
+    if drive.len() != 1 || !drive.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+
+    let drive = drive.to_ascii_lowercase();
+    let root = if drive == "c" {
+        prefix.join("drive_c")
+    } else {
+        prefix.join("dosdevices").join(format!("{}:", drive))
+    };
+
+    Some(
+        rest.split('/')
+            .filter(|part| !part.is_empty())
+            .fold(root, |path, part| path.join(part)),
+    )
+}
+
+/// Folders of a prefix that may hold WoW: the default install folders, plus
+/// every WoW install listed in the prefix's Battle.net product.db
+fn install_candidates(prefix: &Path) -> Vec<PathBuf> {
+    let drive_c = prefix.join("drive_c");
+    let mut candidates = Vec::new();
+
+    let product_db_path = drive_c
+        .join("ProgramData")
+        .join("Battle.net")
+        .join("Agent")
+        .join("product.db");
+    if let Ok(installs) = product_db::read_product_db(&product_db_path) {
+        candidates.extend(
+            installs
+                .iter()
+                .filter(|install| install.is_wow())
+                .filter_map(|install| prefix_path(prefix, &install.install_path)),
+        );
+    }
+
+    candidates.push(drive_c.join("Program Files (x86)").join("World of Warcraft"));
+    candidates.push(drive_c.join("Program Files").join("World of Warcraft"));
+    candidates
+}
+
+/// WoW installations inside the Wine prefixes of a home directory,
+/// keeping only folders with at least one game flavor
+pub fn find_installs(home: &Path) -> Vec<PathBuf> {
+    let mut seen = HashSet::new();
+    find_prefixes(home)
+        .iter()
+        .flat_map(|prefix| install_candidates(prefix))
+        .filter(|path| !flavor::detect_flavors(path).is_empty())
+        .filter(|path| seen.insert(fs::canonicalize(path).unwrap_or_else(|_| path.clone())))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_flavor(wow_path: &Path, flavor: &str) {
+        fs::create_dir_all(wow_path.join(flavor).join("Interface").join("AddOns")).unwrap();
+    }
+
+    #[test]
+    fn maps_windows_paths_into_the_prefix() {
+        let prefix = Path::new("/home/user/.wine");
+
+        assert_eq!(
+            prefix_path(prefix, "C:/Program Files (x86)/World of Warcraft"),
+            Some(prefix.join("drive_c/Program Files (x86)/World of Warcraft"))
+        );
+        assert_eq!(
+            prefix_path(prefix, "d:\\Games\\WoW"),
+            Some(prefix.join("dosdevices/d:/Games/WoW"))
+        );
+        assert_eq!(prefix_path(prefix, "/not/a/windows/path"), None);
+    }
+
+    #[test]
+    fn finds_installs_in_every_kind_of_prefix() {
+        let home = tempfile::tempdir().unwrap();
+        let home = home.path();
+
+        // Plain Wine, default folder
+        let wine = home.join(".wine/drive_c/Program Files (x86)/World of Warcraft");
+        make_flavor(&wine, "_retail_");
+
+        // Lutris, renamed folders listed in product.db, one on another drive
+        let lutris = home.join("Games/battlenet");
+        let agent = lutris.join("drive_c/ProgramData/Battle.net/Agent");
+        fs::create_dir_all(&agent).unwrap();
+        fs::copy(
+            Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/product_db/wine_prefix.db"),
+            agent.join("product.db"),
+        )
+        .unwrap();
+        make_flavor(&lutris.join("drive_c/Games/WoW Renamed"), "_retail_");
+        make_flavor(&lutris.join("dosdevices/d:/World of Warcraft"), "_classic_era_");
+
+        // Bottles
+        let bottles = home.join(".local/share/bottles/bottles/wow/drive_c/Program Files/World of Warcraft");
+        make_flavor(&bottles, "_classic_");
+
+        // Proton prefix with a WoW folder but no game flavor in it
+        let proton = home.join(".local/share/Steam/steamapps/compatdata/1234/pfx/drive_c/Program Files (x86)/World of Warcraft");
+        fs::create_dir_all(&proton).unwrap();
+
+        assert_eq!(
+            find_installs(home),
+            vec![
+                wine,
+                lutris.join("drive_c/Games/WoW Renamed"),
+                lutris.join("dosdevices/d:/World of Warcraft"),
+                bottles,
+            ]
+        );
+    }
+
+    #[test]
+    fn finds_nothing_without_prefixes() {
+        let home = tempfile::tempdir().unwrap();
+        assert!(find_prefixes(home.path()).is_empty());
+        assert!(find_installs(home.path()).is_empty());
+    }
+}
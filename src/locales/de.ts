@@ -20,6 +20,17 @@ export default {
     languageDesc: 'Wählen Sie Ihre bevorzugte Sprache',
     close: 'Schließen',
   },
+  account: {
+    title: 'GitHub-Konto',
+    description: 'Melde dich an, um private Addons zu prüfen und das GitHub-Anfragelimit zu erhöhen',
+    signIn: 'Mit GitHub anmelden',
+    signOut: 'Abmelden',
+    signedInAs: 'Angemeldet als {login}',
+    enterCode: 'Gib diesen Code auf der GitHub-Seite ein:',
+    openGithub: 'GitHub öffnen',
+    waiting: 'Warte auf Bestätigung...',
+    cancel: 'Abbrechen',
+  },
   wowStatus: {
     noPath: 'WoW-Pfad nicht konfiguriert',
     pathConfigured: 'WoW-Pfad',
@@ -37,19 +48,36 @@ export default {
     upToDate: 'Aktuell',
     updateAvailable: 'Aktualisierung verfügbar',
     notInstalled: 'Nicht installiert',
+    outOfDate: 'Veraltet',
+    outOfDateHint: 'Für eine ältere Spielversion erstellt',
+    rateLimited: 'GitHub-Anfragelimit erreicht, Prüfungen werden um {time} fortgesetzt',
     error: 'Fehler',
     configure: 'Konfigurieren',
     localVersion: 'Lokal',
     remoteVersion: 'Remote',
+    confirmDowngrade: 'Version {remote} ist älter als die installierte Version {local}. Trotzdem installieren?',
     uninstall: {
       title: 'Addon deinstallieren',
       description: 'Sind Sie sicher, dass Sie dieses Addon deinstallieren möchten?',
-      warning: 'Diese Aktion wird das Addon dauerhaft aus Ihrer WoW-Installation entfernen.',
+      warning: 'Dadurch wird das Addon aus deiner WoW-Installation entfernt. Es kann einige Tage lang wiederhergestellt werden.',
+      removeSavedVariables: 'Auch seine SavedVariables entfernen',
+      removeSavedVariablesHint: 'Gespeicherte Einstellungen aller Accounts und Charaktere. Eine Sicherung wird mit dem deinstallierten Addon aufbewahrt.',
       confirm: 'Deinstallieren',
       cancel: 'Abbrechen',
       uninstalling: 'Wird deinstalliert...',
+      done: '{name} deinstalliert',
+      savedVariablesRemoved: '{count} SavedVariables-Datei entfernt, mit dem Addon gesichert: | {count} SavedVariables-Dateien entfernt, mit dem Addon gesichert:',
+      dismiss: 'Schließen',
+      undo: 'Rückgängig',
     },
   },
+  quarantine: {
+    title: 'Kürzlich deinstalliert',
+    description: 'Deinstallierte Addons werden einige Tage aufbewahrt und können wiederhergestellt werden',
+    uninstalledAt: 'Deinstalliert am {date}',
+    savedVariables: '{count} SavedVariables-Datei | {count} SavedVariables-Dateien',
+    restore: 'Wiederherstellen',
+  },
   addonConfig: {
     title: 'Addon konfigurieren',
     repository: 'Repository',
@@ -58,6 +86,9 @@ export default {
     remoteVersion: 'Remote',
     notInstalled: 'Nicht installiert',
     unknown: 'Unbekannt',
+    previousVersions: 'Frühere Versionen',
+    archivedAt: 'Ersetzt am {date}',
+    rollback: 'Zurücksetzen',
     updateConfiguration: 'Aktualisierungskonfiguration',
     updateMode: 'Aktualisierungsmodus',
     updateModeRelease: 'Release',
@@ -20,6 +20,17 @@ export default {
     languageDesc: 'Select your preferred language',
     close: 'Close',
   },
+  account: {
+    title: 'GitHub account',
+    description: 'Sign in to check private addons and raise the GitHub request limit',
+    signIn: 'Sign in with GitHub',
+    signOut: 'Sign out',
+    signedInAs: 'Signed in as {login}',
+    enterCode: 'Enter this code on the GitHub page:',
+    openGithub: 'Open GitHub',
+    waiting: 'Waiting for approval...',
+    cancel: 'Cancel',
+  },
   wowStatus: {
     noPath: 'No WoW path configured',
     pathConfigured: 'WoW Path',
@@ -37,19 +48,36 @@ export default {
     upToDate: 'Up to date',
     updateAvailable: 'Update available',
     notInstalled: 'Not installed',
+    outOfDate: 'Out of date',
+    outOfDateHint: 'Built for an older game version',
+    rateLimited: 'GitHub request limit reached, checks resume at {time}',
     error: 'Error',
     configure: 'Configure',
     localVersion: 'Local',
     remoteVersion: 'Remote',
+    confirmDowngrade: 'Version {remote} is older than the installed version {local}. Install it anyway?',
     uninstall: {
       title: 'Uninstall Addon',
       description: 'Are you sure you want to uninstall this addon?',
-      warning: 'This action will permanently delete the addon from your WoW installation.',
+      warning: 'This will remove the addon from your WoW installation. It can be restored for a few days.',
+      removeSavedVariables: 'Also remove its SavedVariables',
+      removeSavedVariablesHint: 'Settings saved for every account and character. A backup is kept with the uninstalled addon.',
       confirm: 'Uninstall',
       cancel: 'Cancel',
       uninstalling: 'Uninstalling...',
+      done: 'Uninstalled {name}',
+      savedVariablesRemoved: 'Removed {count} SavedVariables file, backed up with the addon: | Removed {count} SavedVariables files, backed up with the addon:',
+      dismiss: 'Dismiss',
+      undo: 'Undo',
     },
   },
+  quarantine: {
+    title: 'Recently uninstalled',
+    description: 'Uninstalled addons are kept for a few days and can be put back',
+    uninstalledAt: 'Uninstalled {date}',
+    savedVariables: '{count} SavedVariables file | {count} SavedVariables files',
+    restore: 'Restore',
+  },
   addonConfig: {
     title: 'Configure Addon',
     repository: 'Repository',
@@ -58,6 +86,9 @@ export default {
     remoteVersion: 'Remote',
     notInstalled: 'Not installed',
     unknown: 'Unknown',
+    previousVersions: 'Previous versions',
+    archivedAt: 'Replaced {date}',
+    rollback: 'Roll back',
     updateConfiguration: 'Update Configuration',
     updateMode: 'Update Mode',
     updateModeRelease: 'Release',
@@ -20,6 +20,17 @@ export default {
     languageDesc: 'Selecciona tu idioma preferido',
     close: 'Cerrar',
   },
+  account: {
+    title: 'Cuenta de GitHub',
+    description: 'Inicia sesión para comprobar addons privados y aumentar el límite de solicitudes de GitHub',
+    signIn: 'Iniciar sesión con GitHub',
+    signOut: 'Cerrar sesión',
+    signedInAs: 'Sesión iniciada como {login}',
+    enterCode: 'Introduce este código en la página de GitHub:',
+    openGithub: 'Abrir GitHub',
+    waiting: 'Esperando aprobación...',
+    cancel: 'Cancelar',
+  },
   wowStatus: {
     noPath: 'Ruta de WoW no configurada',
     pathConfigured: 'Ruta de WoW',
@@ -37,19 +48,36 @@ export default {
     upToDate: 'Actualizado',
     updateAvailable: 'Actualización disponible',
     notInstalled: 'No instalado',
+    outOfDate: 'Obsoleto',
+    outOfDateHint: 'Creado para una versión anterior del juego',
+    rateLimited: 'Límite de solicitudes de GitHub alcanzado, las comprobaciones se reanudan a las {time}',
     error: 'Error',
     configure: 'Configurar',
     localVersion: 'Local',
     remoteVersion: 'Remoto',
+    confirmDowngrade: 'La versión {remote} es más antigua que la versión instalada {local}. ¿Instalarla de todos modos?',
     uninstall: {
       title: 'Desinstalar addon',
       description: '¿Estás seguro de que quieres desinstalar este addon?',
-      warning: 'Esta acción eliminará permanentemente el addon de tu instalación de WoW.',
+      warning: 'Esto quitará el addon de tu instalación de WoW. Podrá restaurarse durante unos días.',
+      removeSavedVariables: 'Eliminar también sus SavedVariables',
+      removeSavedVariablesHint: 'Ajustes guardados de cada cuenta y personaje. Se conserva una copia con el addon desinstalado.',
       confirm: 'Desinstalar',
       cancel: 'Cancelar',
       uninstalling: 'Desinstalando...',
+      done: '{name} desinstalado',
+      savedVariablesRemoved: 'Se eliminó {count} archivo de SavedVariables, guardado con el addon: | Se eliminaron {count} archivos de SavedVariables, guardados con el addon:',
+      dismiss: 'Cerrar',
+      undo: 'Deshacer',
     },
   },
+  quarantine: {
+    title: 'Desinstalados recientemente',
+    description: 'Los addons desinstalados se guardan unos días y se pueden recuperar',
+    uninstalledAt: 'Desinstalado el {date}',
+    savedVariables: '{count} archivo de SavedVariables | {count} archivos de SavedVariables',
+    restore: 'Restaurar',
+  },
   addonConfig: {
     title: 'Configurar addon',
     repository: 'Repositorio',
@@ -58,6 +86,9 @@ export default {
     remoteVersion: 'Remoto',
     notInstalled: 'No instalado',
     unknown: 'Desconocido',
+    previousVersions: 'Versiones anteriores',
+    archivedAt: 'Reemplazada el {date}',
+    rollback: 'Volver a esta versión',
     updateConfiguration: 'Configuración de actualización',
     updateMode: 'Modo de actualización',
     updateModeRelease: 'Versión',
@@ -20,6 +20,17 @@ export default {
     languageDesc: 'Sélectionnez votre langue préférée',
     close: 'Fermer',
   },
+  account: {
+    title: 'Compte GitHub',
+    description: 'Connectez-vous pour vérifier les addons privés et augmenter la limite de requêtes GitHub',
+    signIn: 'Se connecter avec GitHub',
+    signOut: 'Se déconnecter',
+    signedInAs: 'Connecté en tant que {login}',
+    enterCode: 'Saisissez ce code sur la page GitHub :',
+    openGithub: 'Ouvrir GitHub',
+    waiting: 'En attente d\'approbation...',
+    cancel: 'Annuler',
+  },
   wowStatus: {
     noPath: 'Chemin WoW non configuré',
     pathConfigured: 'Chemin WoW',
@@ -37,19 +48,36 @@ export default {
     upToDate: 'À jour',
     updateAvailable: 'Mise à jour disponible',
     notInstalled: 'Non installé',
+    outOfDate: 'Obsolète',
+    outOfDateHint: 'Conçu pour une version antérieure du jeu',
+    rateLimited: 'Limite de requêtes GitHub atteinte, reprise des vérifications à {time}',
     error: 'Erreur',
     configure: 'Configurer',
     localVersion: 'Local',
     remoteVersion: 'Distant',
+    confirmDowngrade: "La version {remote} est plus ancienne que la version installée {local}. L'installer quand même ?",
     uninstall: {
       title: 'Désinstaller l\'addon',
       description: 'Êtes-vous sûr de vouloir désinstaller cet addon ?',
-      warning: 'Cette action supprimera définitivement l\'addon de votre installation WoW.',
+      warning: 'Cette action retirera l\'addon de votre installation WoW. Il pourra être restauré pendant quelques jours.',
+      removeSavedVariables: 'Supprimer aussi ses SavedVariables',
+      removeSavedVariablesHint: 'Paramètres enregistrés pour chaque compte et personnage. Une sauvegarde est conservée avec l\'addon désinstallé.',
       confirm: 'Désinstaller',
       cancel: 'Annuler',
       uninstalling: 'Désinstallation...',
+      done: '{name} désinstallé',
+      savedVariablesRemoved: '{count} fichier SavedVariables supprimé, sauvegardé avec l\'addon : | {count} fichiers SavedVariables supprimés, sauvegardés avec l\'addon :',
+      dismiss: 'Fermer',
+      undo: 'Annuler',
     },
   },
+  quarantine: {
+    title: 'Désinstallés récemment',
+    description: 'Les addons désinstallés sont conservés quelques jours et peuvent être remis en place',
+    uninstalledAt: 'Désinstallé le {date}',
+    savedVariables: '{count} fichier SavedVariables | {count} fichiers SavedVariables',
+    restore: 'Restaurer',
+  },
   addonConfig: {
     title: 'Configurer l\'addon',
     repository: 'Dépôt',
@@ -58,6 +86,9 @@ export default {
     remoteVersion: 'Distant',
     notInstalled: 'Non installé',
     unknown: 'Inconnu',
+    previousVersions: 'Versions précédentes',
+    archivedAt: 'Remplacée le {date}',
+    rollback: 'Revenir à cette version',
     updateConfiguration: 'Configuration de mise à jour',
     updateMode: 'Mode de mise à jour',
     updateModeRelease: 'Version',
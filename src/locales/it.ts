@@ -20,6 +20,17 @@ export default {
     languageDesc: 'Seleziona la tua lingua preferita',
     close: 'Chiudi',
   },
+  account: {
+    title: 'Account GitHub',
+    description: 'Accedi per controllare gli addon privati e aumentare il limite di richieste GitHub',
+    signIn: 'Accedi con GitHub',
+    signOut: 'Esci',
+    signedInAs: 'Accesso effettuato come {login}',
+    enterCode: 'Inserisci questo codice nella pagina di GitHub:',
+    openGithub: 'Apri GitHub',
+    waiting: 'In attesa di approvazione...',
+    cancel: 'Annulla',
+  },
   wowStatus: {
     noPath: 'Percorso WoW non configurato',
     pathConfigured: 'Percorso WoW',
@@ -37,19 +48,36 @@ export default {
     upToDate: 'Aggiornato',
     updateAvailable: 'Aggiornamento disponibile',
     notInstalled: 'Non installato',
+    outOfDate: 'Obsoleto',
+    outOfDateHint: 'Creato per una versione precedente del gioco',
+    rateLimited: 'Limite di richieste GitHub raggiunto, i controlli riprendono alle {time}',
     error: 'Errore',
     configure: 'Configura',
     localVersion: 'Locale',
     remoteVersion: 'Remoto',
+    confirmDowngrade: 'La versione {remote} è più vecchia della versione installata {local}. Installarla comunque?',
     uninstall: {
       title: 'Disinstalla addon',
       description: 'Sei sicuro di voler disinstallare questo addon?',
-      warning: 'Questa azione eliminerà permanentemente l\'addon dalla tua installazione di WoW.',
+      warning: 'L\'addon verrà rimosso dalla tua installazione di WoW. Potrà essere ripristinato per alcuni giorni.',
+      removeSavedVariables: 'Rimuovi anche le sue SavedVariables',
+      removeSavedVariablesHint: 'Impostazioni salvate per ogni account e personaggio. Un backup viene conservato con l\'addon disinstallato.',
       confirm: 'Disinstalla',
       cancel: 'Annulla',
       uninstalling: 'Disinstallazione...',
+      done: '{name} disinstallato',
+      savedVariablesRemoved: 'Rimosso {count} file SavedVariables, salvato con l\'addon: | Rimossi {count} file SavedVariables, salvati con l\'addon:',
+      dismiss: 'Chiudi',
+      undo: 'Annulla',
     },
   },
+  quarantine: {
+    title: 'Disinstallati di recente',
+    description: 'Gli addon disinstallati vengono conservati per alcuni giorni e possono essere ripristinati',
+    uninstalledAt: 'Disinstallato il {date}',
+    savedVariables: '{count} file SavedVariables | {count} file SavedVariables',
+    restore: 'Ripristina',
+  },
   addonConfig: {
     title: 'Configura addon',
     repository: 'Repository',
@@ -58,6 +86,9 @@ export default {
     remoteVersion: 'Remoto',
     notInstalled: 'Non installato',
     unknown: 'Sconosciuto',
+    previousVersions: 'Versioni precedenti',
+    archivedAt: 'Sostituita il {date}',
+    rollback: 'Ripristina questa versione',
     updateConfiguration: 'Configurazione aggiornamento',
     updateMode: 'Modalità aggiornamento',
     updateModeRelease: 'Release',
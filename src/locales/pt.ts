@@ -20,6 +20,17 @@ export default {
     languageDesc: 'Selecione seu idioma preferido',
     close: 'Fechar',
   },
+  account: {
+    title: 'Conta do GitHub',
+    description: 'Entre para verificar addons privados e aumentar o limite de solicitações do GitHub',
+    signIn: 'Entrar com GitHub',
+    signOut: 'Sair',
+    signedInAs: 'Conectado como {login}',
+    enterCode: 'Digite este código na página do GitHub:',
+    openGithub: 'Abrir GitHub',
+    waiting: 'Aguardando aprovação...',
+    cancel: 'Cancelar',
+  },
   wowStatus: {
     noPath: 'Caminho do WoW não configurado',
     pathConfigured: 'Caminho do WoW',
@@ -37,19 +48,36 @@ export default {
     upToDate: 'Atualizado',
     updateAvailable: 'Atualização disponível',
     notInstalled: 'Não instalado',
+    outOfDate: 'Desatualizado',
+    outOfDateHint: 'Criado para uma versão anterior do jogo',
+    rateLimited: 'Limite de pedidos do GitHub atingido, as verificações recomeçam às {time}',
     error: 'Erro',
     configure: 'Configurar',
     localVersion: 'Local',
     remoteVersion: 'Remoto',
+    confirmDowngrade: 'A versão {remote} é mais antiga que a versão instalada {local}. Instalar mesmo assim?',
     uninstall: {
       title: 'Desinstalar addon',
       description: 'Tem certeza de que deseja desinstalar este addon?',
-      warning: 'Esta ação removerá permanentemente o addon da sua instalação do WoW.',
+      warning: 'Isto removerá o addon da sua instalação do WoW. Ele poderá ser restaurado por alguns dias.',
+      removeSavedVariables: 'Remover também as SavedVariables',
+      removeSavedVariablesHint: 'Configurações salvas de cada conta e personagem. Um backup é mantido com o addon desinstalado.',
       confirm: 'Desinstalar',
       cancel: 'Cancelar',
       uninstalling: 'Desinstalando...',
+      done: '{name} desinstalado',
+      savedVariablesRemoved: '{count} arquivo SavedVariables removido, guardado com o addon: | {count} arquivos SavedVariables removidos, guardados com o addon:',
+      dismiss: 'Fechar',
+      undo: 'Desfazer',
     },
   },
+  quarantine: {
+    title: 'Desinstalados recentemente',
+    description: 'Os addons desinstalados são mantidos por alguns dias e podem ser restaurados',
+    uninstalledAt: 'Desinstalado em {date}',
+    savedVariables: '{count} arquivo SavedVariables | {count} arquivos SavedVariables',
+    restore: 'Restaurar',
+  },
   addonConfig: {
     title: 'Configurar addon',
     repository: 'Repositório',
@@ -58,6 +86,9 @@ export default {
     remoteVersion: 'Remoto',
     notInstalled: 'Não instalado',
     unknown: 'Desconhecido',
+    previousVersions: 'Versões anteriores',
+    archivedAt: 'Substituída em {date}',
+    rollback: 'Voltar a esta versão',
     updateConfiguration: 'Configuração de atualização',
     updateMode: 'Modo de atualização',
     updateModeRelease: 'Release',
@@ -1,5 +1,5 @@
 import { invoke } from '@tauri-apps/api/core';
-import type { AppConfig, AddonInfo, AddonDefinition, GitHubRelease, UpdateMode } from '../types';
+import type { AppConfig, AddonCheckResult, BuildInfo, ChangedFile, DeviceLogin, GameFlavor, GitHubAccount, AddonInfo, AddonVersion, AddonDefinition, GitHubRelease, InstallRecord, QuarantinedAddon, RateLimit, SavedVariablesFile, SchedulerState, UninstallReport, UpdateMode, WowPathValidation } from '../types';
 
 /**
  * Tauri API Service
@@ -19,10 +19,17 @@ export const TauriAPI = {
   },
 
   /**
-   * Validate if a path is a valid WoW installation
+   * Validate a WoW installation and list the game flavors it contains
    */
-  async validateWowPath(path: string): Promise<boolean> {
-    return await invoke<boolean>('validate_wow_path', { path });
+  async validateWowPath(path: string): Promise<WowPathValidation> {
+    return await invoke<WowPathValidation>('validate_wow_path', { path });
+  },
+
+  /**
+   * Read the product, version and Interface number of each installed client
+   */
+  async getBuildInfo(wowPath: string): Promise<BuildInfo[]> {
+    return await invoke<BuildInfo[]>('get_build_info', { wowPath });
   },
 
   // ===========================
@@ -32,15 +39,22 @@ export const TauriAPI = {
   /**
    * Get all Nihui_* addon versions from local installation
    */
-  async getLocalAddonVersions(wowPath: string): Promise<AddonInfo[]> {
-    return await invoke<AddonInfo[]>('get_local_addon_versions', { wowPath });
+  async getLocalAddonVersions(wowPath: string, flavor: GameFlavor): Promise<AddonInfo[]> {
+    return await invoke<AddonInfo[]>('get_local_addon_versions', { wowPath, flavor });
+  },
+
+  /**
+   * Get the addon catalog (remote manifest, cache or built-in list)
+   */
+  async getAddonList(flavor: GameFlavor): Promise<AddonDefinition[]> {
+    return await invoke<AddonDefinition[]>('get_addon_list', { flavor });
   },
 
   /**
-   * Get the hardcoded list of addon definitions
+   * Force a refresh of the remote addon catalog
    */
-  async getAddonList(): Promise<AddonDefinition[]> {
-    return await invoke<AddonDefinition[]>('get_addon_list');
+  async refreshAddonCatalog(flavor: GameFlavor): Promise<AddonDefinition[]> {
+    return await invoke<AddonDefinition[]>('refresh_addon_catalog', { flavor });
   },
 
   // ===========================
@@ -62,10 +76,35 @@ export const TauriAPI = {
   },
 
   /**
-   * Save addon override configuration
+   * Save addon override configuration (flavor omitted = all flavors)
    */
-  async saveAddonOverride(addonName: string, updateMode: UpdateMode, branch?: string): Promise<void> {
-    return await invoke<void>('save_addon_override', { addonName, updateMode, branch: branch || null });
+  async saveAddonOverride(addonName: string, updateMode: UpdateMode, branch?: string, flavor?: GameFlavor): Promise<void> {
+    return await invoke<void>('save_addon_override', { addonName, updateMode, branch: branch || null, flavor: flavor || null });
+  },
+
+  // ===========================
+  // Custom Addons
+  // ===========================
+
+  /**
+   * Add a user-defined addon
+   */
+  async addCustomAddon(addon: AddonDefinition): Promise<void> {
+    return await invoke<void>('add_custom_addon', { addon });
+  },
+
+  /**
+   * Replace a user-defined addon
+   */
+  async updateCustomAddon(localName: string, addon: AddonDefinition): Promise<void> {
+    return await invoke<void>('update_custom_addon', { localName, addon });
+  },
+
+  /**
+   * Remove a user-defined addon
+   */
+  async removeCustomAddon(localName: string): Promise<void> {
+    return await invoke<void>('remove_custom_addon', { localName });
   },
 
   // ===========================
@@ -89,8 +128,8 @@ export const TauriAPI = {
   /**
    * Fetch TOC version from GitHub repository
    */
-  async fetchGithubToc(owner: string, repo: string, branch: string, addonName: string): Promise<string> {
-    return await invoke<string>('fetch_github_toc', { owner, repo, branch, addonName });
+  async fetchGithubToc(owner: string, repo: string, branch: string, addonName: string, flavor: GameFlavor): Promise<string> {
+    return await invoke<string>('fetch_github_toc', { owner, repo, branch, addonName, flavor });
   },
 
   /**
@@ -107,6 +146,90 @@ export const TauriAPI = {
     return await invoke<string>('fetch_github_readme', { owner, repo, branch });
   },
 
+  /**
+   * Check one addon against its GitHub source
+   */
+  async checkAddonUpdate(wowPath: string, flavor: GameFlavor, addonDef: AddonDefinition): Promise<AddonCheckResult> {
+    return await invoke<AddonCheckResult>('check_addon_update', { wowPath, flavor, addonDef });
+  },
+
+  /**
+   * Check every addon of the addon list for updates
+   */
+  async checkAllUpdates(wowPath: string, flavor: GameFlavor): Promise<AddonCheckResult[]> {
+    return await invoke<AddonCheckResult[]>('check_all_updates', { wowPath, flavor });
+  },
+
+  /**
+   * Run a background update check now (results arrive as an event)
+   */
+  async triggerUpdateCheck(): Promise<void> {
+    return await invoke<void>('trigger_update_check');
+  },
+
+  /**
+   * Get the background scheduler state
+   */
+  async getSchedulerState(): Promise<SchedulerState> {
+    return await invoke<SchedulerState>('get_scheduler_state');
+  },
+
+  /**
+   * Get the GitHub API quota reported by the last response (null before any request)
+   */
+  async getRateLimit(): Promise<RateLimit | null> {
+    return await invoke<RateLimit | null>('get_rate_limit');
+  },
+
+  /**
+   * Save the GitHub token in the OS keyring, or remove it with null
+   */
+  async setGithubToken(token: string | null): Promise<void> {
+    return await invoke('set_github_token', { token });
+  },
+
+  /**
+   * Whether a GitHub token is saved
+   */
+  async hasGithubToken(): Promise<boolean> {
+    return await invoke<boolean>('has_github_token');
+  },
+
+  /**
+   * Start a GitHub device login: returns the code to enter at the verification URL
+   */
+  async startGithubLogin(): Promise<DeviceLogin> {
+    return await invoke<DeviceLogin>('start_github_login');
+  },
+
+  /**
+   * Wait until the code is approved on GitHub, then save the token
+   */
+  async completeGithubLogin(): Promise<GitHubAccount> {
+    return await invoke<GitHubAccount>('complete_github_login');
+  },
+
+  /**
+   * Stop waiting for the pending GitHub login
+   */
+  async cancelGithubLogin(): Promise<void> {
+    return await invoke('cancel_github_login');
+  },
+
+  /**
+   * Sign out of GitHub by forgetting the saved token
+   */
+  async logoutGithub(): Promise<void> {
+    return await invoke('logout_github');
+  },
+
+  /**
+   * Get the signed-in GitHub account (null when signed out)
+   */
+  async getGithubAccount(): Promise<GitHubAccount | null> {
+    return await invoke<GitHubAccount | null>('get_github_account');
+  },
+
   // ===========================
   // Addon Installation
   // ===========================
@@ -114,15 +237,79 @@ export const TauriAPI = {
   /**
    * Install or update an addon from GitHub
    */
-  async installAddon(wowPath: string, addonDef: AddonDefinition): Promise<string> {
-    return await invoke<string>('install_addon', { wowPath, addonDef });
+  async installAddon(wowPath: string, flavor: GameFlavor, addonDef: AddonDefinition, allowDowngrade = false): Promise<string> {
+    return await invoke<string>('install_addon', { wowPath, flavor, addonDef, allowDowngrade });
+  },
+
+  /**
+   * Cancel an addon download or extraction in flight
+   */
+  async cancelInstall(addonName: string, flavor: GameFlavor): Promise<void> {
+    return await invoke<void>('cancel_install', { addonName, flavor });
+  },
+
+  /**
+   * List the previous versions of an addon kept in the local cache
+   */
+  async listAddonVersions(addonName: string, flavor: GameFlavor): Promise<AddonVersion[]> {
+    return await invoke<AddonVersion[]>('list_addon_versions', { addonName, flavor });
+  },
+
+  /**
+   * Restore a previous version of an addon
+   */
+  async rollbackAddon(wowPath: string, flavor: GameFlavor, addonName: string, versionId: string): Promise<string> {
+    return await invoke<string>('rollback_addon', { wowPath, flavor, addonName, versionId });
+  },
+
+  /**
+   * Uninstall an addon by moving its directory to the quarantine,
+   * optionally with its SavedVariables files
+   */
+  async uninstallAddon(wowPath: string, flavor: GameFlavor, addonName: string, removeSavedVariables = false): Promise<UninstallReport> {
+    return await invoke<UninstallReport>('uninstall_addon', { wowPath, flavor, addonName, removeSavedVariables });
+  },
+
+  /**
+   * List the SavedVariables files an uninstall would remove
+   */
+  async listSavedVariables(wowPath: string, flavor: GameFlavor, addonName: string): Promise<SavedVariablesFile[]> {
+    return await invoke<SavedVariablesFile[]>('list_saved_variables', { wowPath, flavor, addonName });
+  },
+
+  /**
+   * List uninstalled addons that can still be restored
+   */
+  async listQuarantined(flavor: GameFlavor): Promise<QuarantinedAddon[]> {
+    return await invoke<QuarantinedAddon[]>('list_quarantined', { flavor });
+  },
+
+  /**
+   * Put an uninstalled addon back in place
+   */
+  async restoreAddon(wowPath: string, quarantineId: string): Promise<string> {
+    return await invoke<string>('restore_addon', { wowPath, quarantineId });
+  },
+
+  /**
+   * Get the install record of an addon (null if not installed through the app)
+   */
+  async getInstallRecord(addonName: string, flavor: GameFlavor): Promise<InstallRecord | null> {
+    return await invoke<InstallRecord | null>('get_install_record', { addonName, flavor });
+  },
+
+  /**
+   * Get the install records of every addon installed through the app
+   */
+  async getInstallRecords(flavor: GameFlavor): Promise<InstallRecord[]> {
+    return await invoke<InstallRecord[]>('get_install_records', { flavor });
   },
 
   /**
-   * Uninstall an addon by removing its directory
+   * Get the files of an addon that were edited or deleted since its install
    */
-  async uninstallAddon(wowPath: string, addonName: string): Promise<string> {
-    return await invoke<string>('uninstall_addon', { wowPath, addonName });
+  async getChangedFiles(wowPath: string, addonName: string, flavor: GameFlavor): Promise<ChangedFile[]> {
+    return await invoke<ChangedFile[]>('get_changed_files', { wowPath, addonName, flavor });
   },
 
   /**
@@ -2,6 +2,28 @@
 
 export type UpdateMode = 'release' | 'branch';
 
+export type GameFlavor =
+  | '_retail_'
+  | '_classic_'
+  | '_classic_era_'
+  | '_ptr_'
+  | '_xptr_'
+  | '_beta_'
+  | '_classic_ptr_';
+
+export interface WowPathValidation {
+  valid: boolean;
+  flavors: GameFlavor[]; // Flavors with an Interface/AddOns folder
+}
+
+export interface BuildInfo {
+  flavor: GameFlavor;
+  product: string; // Battle.net product code, e.g. "wow"
+  version: string; // e.g. "11.0.2.56421"
+  build: number;
+  interface: number; // TOC Interface number of the client, e.g. 110002
+}
+
 export interface AddonOverride {
   update_mode?: UpdateMode;
   branch?: string;
@@ -13,6 +35,19 @@ export interface AppConfig {
   minimize_on_startup: boolean;
   language: string;
   addon_overrides: Record<string, AddonOverride>;
+  flavor_overrides?: Partial<Record<GameFlavor, Record<string, AddonOverride>>>; // Applied over addon_overrides
+  active_flavor?: GameFlavor; // Flavor shown in the app
+  catalog_url?: string | null; // null = default remote catalog
+  custom_addons?: AddonDefinition[];
+  check_concurrency?: number;
+  check_interval_minutes?: number; // 0 = background checks disabled
+  version_retention?: number; // Previous versions kept per addon, 0 = none
+  quarantine_days?: number; // Days uninstalled addons stay restorable, 0 = forever
+  last_seen_builds?: Partial<Record<GameFlavor, number>>; // Managed by the backend
+  http_timeout_secs?: number; // Connect and read timeout of HTTP requests
+  github_base_url?: string | null; // Replaces the GitHub hosts, e.g. with a mock server
+  github_oauth_url?: string | null; // Replaces the host of the GitHub login endpoints
+  github_client_id?: string | null; // OAuth app used to sign in, null = the built-in one
 }
 
 export interface AddonDefinition {
@@ -31,6 +66,30 @@ export interface AddonInfo {
   path: string;
 }
 
+export interface InstalledFile {
+  path: string;
+  sha256: string;
+}
+
+export type FileState = 'unchanged' | 'modified' | 'missing';
+
+export interface ChangedFile {
+  path: string;
+  state: FileState;
+}
+
+export interface InstallRecord {
+  local_name: string;
+  flavor: GameFlavor;
+  source_url: string;
+  commit_sha: string | null;
+  tag: string | null;
+  update_mode: UpdateMode;
+  branch: string | null;
+  installed_at: number; // Unix timestamp (seconds)
+  files: InstalledFile[];
+}
+
 export interface GitHubRelease {
   tag_name: string;
   name: string;
@@ -38,12 +97,116 @@ export interface GitHubRelease {
   zipball_url: string;
 }
 
+export type VersionComparison = 'newer' | 'older' | 'equal' | 'incomparable';
+
+export interface UpdateCheck {
+  local_name: string;
+  flavor: GameFlavor;
+  installed: boolean;
+  local_version: string | null;
+  remote_version: string | null;
+  installed_commit: string | null;
+  remote_commit: string | null;
+  update_available: boolean;
+  new_commits: boolean; // Remote head moved since install, even without a version bump
+  comparison: VersionComparison | null; // Remote version relative to the local one
+  interface_outdated: boolean; // TOC Interface older than the installed client
+}
+
+export type AddonUpdateStatus =
+  | { state: 'checking' }
+  | { state: 'not-installed'; remote_version: string | null }
+  | { state: 'up-to-date'; local_version: string | null; remote_version: string | null }
+  | { state: 'update-available'; local_version: string | null; remote_version: string | null; new_commits: boolean; downgrade: boolean }
+  | { state: 'local-ahead'; local_version: string | null; remote_version: string | null }
+  | { state: 'error'; local_version: string | null; message: string }
+  | { state: 'rate-limited'; local_version: string | null; reset_at: number };
+
+export interface AddonCheckResult {
+  local_name: string;
+  flavor: GameFlavor;
+  status: AddonUpdateStatus;
+  interface_outdated: boolean; // TOC Interface older than the installed client
+}
+
+export interface UpdateCheckEvent {
+  checked_at: number; // Unix timestamp (seconds)
+  results: AddonCheckResult[];
+}
+
+export interface RateLimit {
+  limit: number;
+  remaining: number;
+  reset_at: number; // Unix timestamp (seconds) when the quota is restored
+}
+
+export interface DeviceLogin {
+  user_code: string; // Code the user enters on GitHub
+  verification_uri: string;
+  expires_in: number; // Seconds the code stays valid
+}
+
+export interface GitHubAccount {
+  login: string;
+  name: string | null;
+  avatar_url: string;
+  html_url: string;
+}
+
+export interface SchedulerState {
+  last_check: number; // Unix timestamp (seconds), 0 = never
+  available_updates: string[];
+}
+
+export type InstallPhase = 'download' | 'extract' | 'copy';
+
+export interface InstallProgress {
+  addon: string;
+  flavor: GameFlavor;
+  phase: InstallPhase;
+  progress: number; // Bytes while downloading, files while extracting/copying
+  total: number | null;
+}
+
+export interface AddonVersion {
+  id: string;
+  local_name: string;
+  version: string | null; // TOC version of the archived files
+  archived_at: number; // Unix timestamp (seconds)
+  record: InstallRecord | null; // null if installed outside the app
+}
+
+export interface QuarantinedAddon {
+  id: string;
+  local_name: string;
+  flavor: GameFlavor;
+  version: string | null; // TOC version of the quarantined files
+  quarantined_at: number; // Unix timestamp (seconds)
+  record: InstallRecord | null; // null if installed outside the app
+  saved_variables: SavedVariablesFile[]; // Removed along with the addon
+}
+
+export interface SavedVariablesFile {
+  path: string; // Relative to the WTF folder
+  account: string;
+  realm: string | null; // null for account-wide SavedVariables
+  character: string | null;
+}
+
+export interface UninstallReport {
+  local_name: string;
+  quarantine_id: string; // Pass to restoreAddon to undo
+  saved_variables: SavedVariablesFile[]; // Empty unless requested
+}
+
 export interface AddonStatus {
   definition: AddonDefinition;
   local_info?: AddonInfo; // undefined if not installed
   remote_version?: string;
   is_installed: boolean;
   update_available: boolean;
+  is_downgrade?: boolean; // Installing the remote version would downgrade the addon
+  interface_outdated?: boolean; // Shown as "out of date" in game
   status: 'checking' | 'not-installed' | 'up-to-date' | 'update-available' | 'error';
   error?: string;
 }